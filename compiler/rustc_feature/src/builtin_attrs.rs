@@ -923,6 +923,14 @@ pub struct BuiltinAttribute {
         rustc_no_mir_inline, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::Yes,
         "#[rustc_no_mir_inline] prevents the MIR inliner from inlining a function while not affecting codegen"
     ),
+    rustc_attr!(
+        rustc_mir_sroa, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::Yes,
+        "#[rustc_mir_sroa] runs scalar replacement of aggregates on a function even where it is not enabled"
+    ),
+    rustc_attr!(
+        rustc_no_mir_sroa, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::Yes,
+        "#[rustc_no_mir_sroa] prevents scalar replacement of aggregates from running on a function"
+    ),
     rustc_attr!(
         rustc_intrinsic_must_be_overridden, Normal, template!(Word), ErrorFollowing, EncodeCrossCrate::Yes,
         "the `#[rustc_intrinsic_must_be_overridden]` attribute is used to declare intrinsics without real bodies",
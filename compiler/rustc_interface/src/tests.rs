@@ -701,6 +701,8 @@ macro_rules! untracked {
     untracked!(macro_backtrace, true);
     untracked!(meta_stats, true);
     untracked!(mir_include_spans, true);
+    untracked!(mir_sroa_escape_reasons, true);
+    untracked!(mir_sroa_stats, true);
     untracked!(nll_facts, true);
     untracked!(no_analysis, true);
     untracked!(no_leak_check, true);
@@ -797,6 +799,14 @@ macro_rules! tracked {
     tracked!(mir_enable_passes, vec![("DestProp".to_string(), false)]);
     tracked!(mir_keep_place_mention, true);
     tracked!(mir_opt_level, Some(4));
+    tracked!(mir_sroa, Some(true));
+    tracked!(mir_sroa_args, true);
+    tracked!(mir_sroa_local_growth_limit, Some(2));
+    tracked!(mir_sroa_max_fragments, Some(8));
+    tracked!(mir_sroa_partial, true);
+    tracked!(mir_sroa_preserve, vec![String::from("x")]);
+    tracked!(mir_sroa_return_place, true);
+    tracked!(mir_sroa_skip_trivial, true);
     tracked!(move_size_limit, Some(4096));
     tracked!(mutable_noalias, false);
     tracked!(
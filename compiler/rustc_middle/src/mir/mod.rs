@@ -161,6 +161,12 @@ fn is_enabled(&self, _sess: &Session) -> bool {
         true
     }
 
+    /// Returns `Some` to override both `is_enabled` and `-Zmir-enable-passes` for `body`, e.g.
+    /// because of an attribute on its function.
+    fn is_enabled_for_body(&self, _tcx: TyCtxt<'tcx>, _body: &Body<'tcx>) -> Option<bool> {
+        None
+    }
+
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>);
 
     fn is_mir_dump_enabled(&self) -> bool {
@@ -1269,15 +1275,16 @@ fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
 #[derive(Clone, Debug, TyEncodable, TyDecodable, HashStable, TypeFoldable, TypeVisitable)]
 pub struct VarDebugInfoFragment<'tcx> {
     /// Type of the original user variable.
-    /// This cannot contain a union or an enum.
+    /// This cannot contain a union or an enum with several variants.
     pub ty: Ty<'tcx>,
 
     /// Where in the composite user variable this fragment is,
     /// represented as a "projection" into the composite variable.
     /// At lower levels, this corresponds to a byte/bit range.
     ///
-    /// This can only contain `PlaceElem::Field`.
-    // FIXME support this for `enum`s by either using DWARF's
+    /// This can only contain `PlaceElem::Field`, `PlaceElem::ConstantIndex` with
+    /// `from_end: false` for arrays, and `PlaceElem::Downcast` to the only variant of an enum.
+    // FIXME support this for multi-variant `enum`s by either using DWARF's
     // more advanced control-flow features (unsupported by LLVM?)
     // to match on the discriminant, or by using custom type debuginfo
     // with non-overlapping variants for the composite variable.
@@ -7,7 +7,7 @@
 pub struct MirPatch<'tcx> {
     patch_map: IndexVec<BasicBlock, Option<TerminatorKind<'tcx>>>,
     new_blocks: Vec<BasicBlockData<'tcx>>,
-    new_statements: Vec<(Location, StatementKind<'tcx>)>,
+    new_statements: Vec<(Location, Option<SourceInfo>, StatementKind<'tcx>)>,
     new_locals: Vec<LocalDecl<'tcx>>,
     resume_block: Option<BasicBlock>,
     // Only for unreachable in cleanup path.
@@ -187,7 +187,19 @@ pub fn patch_terminator(&mut self, block: BasicBlock, new: TerminatorKind<'tcx>)
 
     pub fn add_statement(&mut self, loc: Location, stmt: StatementKind<'tcx>) {
         debug!("MirPatch: add_statement({:?}, {:?})", loc, stmt);
-        self.new_statements.push((loc, stmt));
+        self.new_statements.push((loc, None, stmt));
+    }
+
+    /// Like `add_statement`, but the new statement gets `source_info` instead of the source info
+    /// of the statement currently at `loc`.
+    pub fn add_statement_with_source_info(
+        &mut self,
+        loc: Location,
+        source_info: SourceInfo,
+        stmt: StatementKind<'tcx>,
+    ) {
+        debug!("MirPatch: add_statement_with_source_info({:?}, {:?})", loc, stmt);
+        self.new_statements.push((loc, Some(source_info), stmt));
     }
 
     pub fn add_assign(&mut self, loc: Location, place: Place<'tcx>, rv: Rvalue<'tcx>) {
@@ -225,14 +237,15 @@ pub fn apply(self, body: &mut Body<'tcx>) {
 
         let mut delta = 0;
         let mut last_bb = START_BLOCK;
-        for (mut loc, stmt) in new_statements {
+        for (mut loc, source_info, stmt) in new_statements {
             if loc.block != last_bb {
                 delta = 0;
                 last_bb = loc.block;
             }
             debug!("MirPatch: adding statement {:?} at loc {:?}+{}", stmt, loc, delta);
             loc.statement_index += delta;
-            let source_info = Self::source_info_for_index(&body[loc.block], loc);
+            let source_info =
+                source_info.unwrap_or_else(|| Self::source_info_for_index(&body[loc.block], loc));
             body[loc.block]
                 .statements
                 .insert(loc.statement_index, Statement { source_info, kind: stmt });
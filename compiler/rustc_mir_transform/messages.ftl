@@ -23,6 +23,21 @@ mir_transform_must_not_suspend = {$pre}`{$def_path}`{$post} held across a suspen
     .help = consider using a block (`{"{ ... }"}`) to shrink the value's scope, ending before the suspend point
 mir_transform_operation_will_panic = this operation will panic at runtime
 
+mir_transform_sroa_escape_reason = SROA leaves `{$local}` of `{$def_path}` whole: {$reason ->
+    [arg_or_return] it is the return place or an argument
+    [excluded] it is excluded
+    [union] it is a union
+    [enum] it is an enum with several variants
+    [simd] it is a SIMD vector
+    [niche] it provides a niche, or its layout is unknown
+    [address_taken] its address is taken at {$location}
+    [call_destination] it is the destination of the call at {$location}
+    [inline_asm] it is an output of the inline assembly at {$location}
+    [retagged] it is retagged at {$location}
+    [whole_result] it is only assigned results which cannot be split, like the one at {$location}
+    *[used] it is used as a whole at {$location}
+}
+
 mir_transform_unaligned_packed_ref = reference to packed field is unaligned
     .note = packed structs are only aligned by one byte, and many modern architectures penalize unaligned field accesses
     .note_ub = creating a misaligned reference is undefined behavior (even if that reference is never dereferenced)
@@ -126,3 +126,16 @@ pub(crate) struct MustNotSuspendReason {
     pub span: Span,
     pub reason: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_sroa_escape_reason)]
+pub(crate) struct SroaEscapeReason {
+    #[primary_span]
+    pub span: Span,
+    pub local: String,
+    pub def_path: String,
+    pub reason: &'static str,
+    /// The location of the use which made the local escape, or nothing for the reasons which
+    /// don't have one.
+    pub location: String,
+}
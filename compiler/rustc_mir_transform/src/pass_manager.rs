@@ -125,7 +125,9 @@ fn run_passes_inner<'tcx>(
         for pass in passes {
             let name = pass.name();
 
-            if !should_run_pass(tcx, *pass) {
+            let enabled =
+                pass.is_enabled_for_body(tcx, body).unwrap_or_else(|| should_run_pass(tcx, *pass));
+            if !enabled {
                 continue;
             };
 
@@ -1,19 +1,41 @@
 use rustc_data_structures::flat_map_in_place::FlatMapInPlace;
+use rustc_hir::def_id::DefId;
 use rustc_index::bit_set::{BitSet, GrowableBitSet};
 use rustc_index::IndexVec;
-use rustc_middle::bug;
 use rustc_middle::mir::patch::MirPatch;
 use rustc_middle::mir::visit::*;
 use rustc_middle::mir::*;
 use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_middle::{bug, span_bug};
 use rustc_mir_dataflow::value_analysis::{excluded_locals, iter_fields};
+use rustc_span::sym;
 use rustc_target::abi::{FieldIdx, FIRST_VARIANT};
 
+use crate::errors;
+use crate::pass_manager::validate_body;
+
 pub struct ScalarReplacementOfAggregates;
 
 impl<'tcx> MirPass<'tcx> for ScalarReplacementOfAggregates {
     fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
-        sess.mir_opt_level() >= 2
+        match sess.opts.unstable_opts.mir_sroa {
+            // Unlike the rest of `-Zmir-opt-level=2`, SROA can be opted into at level 1.
+            Some(enabled) => enabled && sess.mir_opt_level() >= 1,
+            None => sess.mir_opt_level() >= 2,
+        }
+    }
+
+    /// `#[rustc_mir_sroa]` and `#[rustc_no_mir_sroa]` force SROA on or off for one function, so
+    /// that its effect can be isolated without changing the flags of the whole crate.
+    fn is_enabled_for_body(&self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Option<bool> {
+        let def_id = body.source.def_id();
+        if tcx.has_attr(def_id, sym::rustc_no_mir_sroa) {
+            Some(false)
+        } else if tcx.has_attr(def_id, sym::rustc_mir_sroa) {
+            Some(true)
+        } else {
+            None
+        }
     }
 
     #[instrument(level = "debug", skip(self, tcx, body))]
@@ -25,34 +47,197 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
             return;
         }
 
-        let mut excluded = excluded_locals(body);
+        let (mut excluded, borrowed) = if tcx.sess.opts.unstable_opts.mir_sroa_partial {
+            borrowed_fields(body)
+        } else {
+            (excluded_locals(body), IndexVec::new())
+        };
+        preserve_named_locals(tcx, body, &mut excluded);
         let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        // With `-Zmir-sroa-local-growth-limit`, stop flattening before the body has more than
+        // that many times the locals it started with.
+        let max_locals = tcx
+            .sess
+            .opts
+            .unstable_opts
+            .mir_sroa_local_growth_limit
+            .map(|factor| body.local_decls.len().saturating_mul(factor));
+        if tcx.sess.opts.unstable_opts.mir_sroa_escape_reasons {
+            let reasons = escape_reasons(tcx, param_env, &excluded, body);
+            emit_escape_reasons(tcx, body, &reasons);
+        }
+        let original_locals = body.local_decls.len();
+        let mut whole_temp_count = 0;
+        let mut stats = SroaStats::default();
+        let mut round = 0;
         loop {
             debug!(?excluded);
             let escaping = escaping_locals(tcx, param_env, &excluded, body);
             debug!(?escaping);
-            let replacements = compute_flattening(tcx, param_env, body, escaping);
+            let replacements =
+                compute_flattening(tcx, param_env, body, escaping, &borrowed, max_locals);
             debug!(?replacements);
-            let all_dead_locals = replace_flattened_locals(tcx, body, replacements);
-            if !all_dead_locals.is_empty() {
+            let partial = replacements.partially_flattened_locals();
+            let (all_dead_locals, whole_temps) =
+                replace_flattened_locals(tcx, body, replacements, &mut stats);
+            // The pass manager only validates once the whole pass is done. Validate each round
+            // of flattening, so that a broken rewrite is attributed to the round that made it.
+            if tcx.sess.opts.unstable_opts.validate_mir {
+                validate_body(tcx, body, format!("after SROA flattening round {round}"));
+            }
+            round += 1;
+            stats.replaced_locals += all_dead_locals.count();
+            if !all_dead_locals.is_empty() || !partial.is_empty() {
                 excluded.union(&all_dead_locals);
+                // The fields left in partially flattened locals are borrowed, so there is nothing
+                // more to flatten in them.
+                excluded.union(&partial);
                 excluded = {
                     let mut growable = GrowableBitSet::from(excluded);
                     growable.ensure(body.local_decls.len());
                     growable.into()
                 };
+                whole_temp_count += whole_temps.len();
+                for temp in whole_temps {
+                    excluded.insert(temp);
+                }
             } else {
                 break;
             }
         }
+
+        if tcx.sess.opts.unstable_opts.mir_sroa_stats {
+            // The temporaries holding whole results are not fragments of anything.
+            stats.fragments = body.local_decls.len() - original_locals - whole_temp_count;
+            stats.print(tcx, body.source.def_id());
+        }
+    }
+}
+
+/// What SROA did to a body, printed with `-Zmir-sroa-stats`.
+#[derive(Default)]
+struct SroaStats {
+    /// Locals which were split into fragments, over all rounds.
+    replaced_locals: usize,
+    /// Locals created for the fragments, over all rounds.
+    fragments: usize,
+    /// Aggregate assignments which were split into assignments to the fragments.
+    expanded_aggregates: usize,
+}
+
+impl SroaStats {
+    fn print(&self, tcx: TyCtxt<'_>, def_id: DefId) {
+        let prefix = "sroa-stats";
+        eprintln!("{prefix} SROA STATS for `{}`", tcx.def_path_str(def_id));
+        eprintln!("{} {:<23}{:>10}", prefix, "Replaced locals", self.replaced_locals);
+        eprintln!("{} {:<23}{:>10}", prefix, "Fragments", self.fragments);
+        eprintln!("{} {:<23}{:>10}", prefix, "Expanded aggregates", self.expanded_aggregates);
+    }
+}
+
+/// Emit a note saying why each local of `body` is not flattened, with
+/// `-Zmir-sroa-escape-reasons`. The note points at the use which made the local escape, or at its
+/// declaration. The locals which can be flattened are left out.
+fn emit_escape_reasons<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    reasons: &IndexVec<Local, Option<EscapeReason>>,
+) {
+    let def_path = tcx.def_path_str(body.source.def_id());
+    for (local, reason) in reasons.iter_enumerated() {
+        let Some(reason) = *reason else { continue };
+        let span = match reason.location() {
+            Some(location) => body.source_info(location).span,
+            None => body.local_decls[local].source_info.span,
+        };
+        tcx.dcx().emit_note(errors::SroaEscapeReason {
+            span,
+            local: format!("{local:?}"),
+            def_path: def_path.clone(),
+            reason: reason.diagnostic_key(),
+            location: reason.location().map(|location| format!("{location:?}")).unwrap_or_default(),
+        });
+    }
+}
+
+/// Exclude the locals backing the user variables named by `-Zmir-sroa-preserve`, so that a
+/// single variable can be kept whole when bisecting a miscompile.
+fn preserve_named_locals<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, excluded: &mut BitSet<Local>) {
+    let preserve = &tcx.sess.opts.unstable_opts.mir_sroa_preserve;
+    if preserve.is_empty() {
+        return;
+    }
+    for info in &body.var_debug_info {
+        if info.composite.is_none()
+            && let VarDebugInfoContents::Place(place) = info.value
+            && let Some(local) = place.as_local()
+            && preserve.iter().any(|name| info.name.as_str() == name)
+        {
+            debug!(?local, name = ?info.name, "preserved from SROA");
+            excluded.insert(local);
+        }
+    }
+}
+
+/// With `-Zmir-sroa-partial`, this replaces `excluded_locals`: a local is not excluded when the
+/// only pointers to it are references to some of its fields. Those fields are returned instead,
+/// so that they stay in the local while the other fields are flattened.
+///
+/// This relies on a reference to a field only giving access to that field. A raw pointer to a
+/// field may be used to reach the other fields, so it still excludes the whole local, and so do
+/// drops and inline assembly outputs.
+fn borrowed_fields(body: &Body<'_>) -> (BitSet<Local>, IndexVec<Local, Vec<FieldIdx>>) {
+    struct Collector {
+        excluded: BitSet<Local>,
+        borrowed: IndexVec<Local, Vec<FieldIdx>>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for Collector {
+        fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _location: Location) {
+            if !(context.is_borrow()
+                || context.is_address_of()
+                || context.is_drop()
+                || context == PlaceContext::MutatingUse(MutatingUseContext::AsmOutput))
+                || place.is_indirect()
+            {
+                return;
+            }
+            let field = match &place.projection[..] {
+                &[PlaceElem::Field(field, _), ..] if context.is_borrow() => Some(field),
+                &[PlaceElem::ConstantIndex { offset, from_end: false, .. }, ..]
+                    if context.is_borrow() =>
+                {
+                    Some(FieldIdx::from_usize(offset as usize))
+                }
+                _ => None,
+            };
+            match field {
+                Some(field) => {
+                    let fields = &mut self.borrowed[place.local];
+                    if !fields.contains(&field) {
+                        fields.push(field);
+                    }
+                }
+                None => {
+                    self.excluded.insert(place.local);
+                }
+            }
+        }
     }
+
+    let mut collector = Collector {
+        excluded: BitSet::new_empty(body.local_decls.len()),
+        borrowed: IndexVec::from_elem(Vec::new(), &body.local_decls),
+    };
+    collector.visit_body(body);
+    (collector.excluded, collector.borrowed)
 }
 
 /// Identify all locals that are not eligible for SROA.
 ///
 /// There are 3 cases:
 /// - the aggregated local is used or passed to other code (function parameters and arguments);
-/// - the locals is a union or an enum;
+/// - the locals is a union or an enum with several variants;
 /// - the local's address is taken, and thus the relative addresses of the fields are observable to
 ///   client code.
 fn escaping_locals<'tcx>(
@@ -61,59 +246,240 @@ fn escaping_locals<'tcx>(
     excluded: &BitSet<Local>,
     body: &Body<'tcx>,
 ) -> BitSet<Local> {
-    let is_excluded_ty = |ty: Ty<'tcx>| {
-        if ty.is_union() || ty.is_enum() {
-            return true;
+    let reasons = escape_reasons(tcx, param_env, excluded, body);
+    let mut set = BitSet::new_empty(body.local_decls.len());
+    for (local, reason) in reasons.iter_enumerated() {
+        if let Some(reason) = reason {
+            debug!(?local, ?reason, location = ?reason.location());
+            set.insert(local);
+        }
+    }
+    set
+}
+
+/// Why `escaping_locals` considered a local ineligible for SROA, as reported by
+/// `-Zmir-sroa-escape-reasons`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum EscapeReason {
+    /// The local is the return place or a function argument.
+    ArgOrReturn,
+    /// The local was excluded by `excluded_locals` or `-Zmir-sroa-preserve`, or replaced by a
+    /// previous iteration, and none of the other reasons applies. A local whose address is taken
+    /// is excluded, but reported as `AddressTaken`.
+    Excluded,
+    Union,
+    /// An enum with several variants. Single-variant enums are flattened like structs.
+    Enum,
+    /// `#[repr(simd)]` types are not de-optimized into an array.
+    Simd,
+    /// A single-field wrapper that provides a niche, or whose layout we cannot compute.
+    Niche,
+    /// The address of the local is taken at this location.
+    AddressTaken(Location),
+    /// The local is the destination of a call at this location.
+    CallDestination(Location),
+    /// The local is an output of an inline assembly block at this location.
+    InlineAsm(Location),
+    /// The local is retagged as a whole at this location. Retags are address-sensitive, so they
+    /// cannot be split into retags of the fragments. Retags of a field are just rewritten to
+    /// retags of the corresponding fragment.
+    Retagged(Location),
+    /// The local is used as a whole in a way we do not know how to split at this location.
+    Used(Location),
+    /// The local is only assigned results which cannot be split, like the one at this location.
+    /// Copying its fields out of a temporary holding the whole result would only rename it.
+    WholeResult(Location),
+}
+
+impl EscapeReason {
+    fn from_context(context: PlaceContext, location: Location) -> Self {
+        match context {
+            PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::SharedBorrow
+                | NonMutatingUseContext::FakeBorrow
+                | NonMutatingUseContext::AddressOf,
+            )
+            | PlaceContext::MutatingUse(
+                MutatingUseContext::Borrow | MutatingUseContext::AddressOf,
+            ) => EscapeReason::AddressTaken(location),
+            PlaceContext::MutatingUse(MutatingUseContext::Call) => {
+                EscapeReason::CallDestination(location)
+            }
+            PlaceContext::MutatingUse(MutatingUseContext::AsmOutput) => {
+                EscapeReason::InlineAsm(location)
+            }
+            PlaceContext::MutatingUse(MutatingUseContext::Retag) => {
+                EscapeReason::Retagged(location)
+            }
+            _ => EscapeReason::Used(location),
+        }
+    }
+
+    /// The variant of the `-Zmir-sroa-escape-reasons` note for this reason.
+    fn diagnostic_key(self) -> &'static str {
+        match self {
+            EscapeReason::ArgOrReturn => "arg_or_return",
+            EscapeReason::Excluded => "excluded",
+            EscapeReason::Union => "union",
+            EscapeReason::Enum => "enum",
+            EscapeReason::Simd => "simd",
+            EscapeReason::Niche => "niche",
+            EscapeReason::AddressTaken(_) => "address_taken",
+            EscapeReason::CallDestination(_) => "call_destination",
+            EscapeReason::InlineAsm(_) => "inline_asm",
+            EscapeReason::Retagged(_) => "retagged",
+            EscapeReason::Used(_) => "used",
+            EscapeReason::WholeResult(_) => "whole_result",
+        }
+    }
+
+    /// The location of the use which made the local escape, if any.
+    fn location(self) -> Option<Location> {
+        match self {
+            EscapeReason::AddressTaken(location)
+            | EscapeReason::CallDestination(location)
+            | EscapeReason::InlineAsm(location)
+            | EscapeReason::Retagged(location)
+            | EscapeReason::Used(location)
+            | EscapeReason::WholeResult(location) => Some(location),
+            EscapeReason::ArgOrReturn
+            | EscapeReason::Excluded
+            | EscapeReason::Union
+            | EscapeReason::Enum
+            | EscapeReason::Simd
+            | EscapeReason::Niche => None,
+        }
+    }
+}
+
+/// Whether the fragments of a local assigned `rvalue` are copied out of a temporary holding its
+/// whole result, as the rvalue itself cannot be split.
+fn needs_whole_temp(rvalue: &Rvalue<'_>) -> bool {
+    match rvalue {
+        Rvalue::BinaryOp(op, _) => op.is_overflowing(),
+        Rvalue::Cast(CastKind::Transmute, ..) => true,
+        _ => false,
+    }
+}
+
+/// Compute, for each local, the first reason for which it is not eligible for SROA, or `None` if
+/// it can be flattened. This is the analysis behind `escaping_locals`, which only keeps whether
+/// there is a reason. The reasons are logged, and reported with `-Zmir-sroa-escape-reasons`.
+fn escape_reasons<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    excluded: &BitSet<Local>,
+    body: &Body<'tcx>,
+) -> IndexVec<Local, Option<EscapeReason>> {
+    let excluded_ty_reason = |ty: Ty<'tcx>| {
+        if ty.is_union() {
+            return Some(EscapeReason::Union);
+        }
+        if let ty::Adt(def, _) = ty.kind()
+            && def.is_enum()
+            && def.variants().len() != 1
+        {
+            return Some(EscapeReason::Enum);
         }
         if let ty::Adt(def, _args) = ty.kind() {
             if def.repr().simd() {
                 // Exclude #[repr(simd)] types so that they are not de-optimized into an array
-                return true;
+                return Some(EscapeReason::Simd);
             }
-            // We already excluded unions and enums, so this ADT must have one variant
+            // We already excluded unions and enums with several variants, so this ADT must have
+            // one variant
             let variant = def.variant(FIRST_VARIANT);
             if variant.fields.len() > 1 {
                 // If this has more than one field, it cannot be a wrapper that only provides a
                 // niche, so we do not want to automatically exclude it.
-                return false;
+                return None;
             }
             let Ok(layout) = tcx.layout_of(param_env.and(ty)) else {
                 // We can't get the layout
-                return true;
+                return Some(EscapeReason::Niche);
             };
             if layout.layout.largest_niche().is_some() {
                 // This type has a niche
-                return true;
+                return Some(EscapeReason::Niche);
             }
         }
         // Default for non-ADTs
-        false
+        None
     };
 
-    let mut set = BitSet::new_empty(body.local_decls.len());
-    set.insert_range(RETURN_PLACE..=Local::from_usize(body.arg_count));
+    let mut reasons = IndexVec::from_elem(None, &body.local_decls);
+    // With `-Zmir-sroa-return-place`, the return place is flattened like other locals, and
+    // reassembled with an aggregate before each `return`, so it must be a type we can build one of.
+    let flatten_return_place = tcx.sess.opts.unstable_opts.mir_sroa_return_place
+        && matches!(body.return_ty().kind(), ty::Tuple(..) | ty::Adt(..) | ty::Array(..));
+    if !flatten_return_place {
+        reasons[RETURN_PLACE] = Some(EscapeReason::ArgOrReturn);
+    }
+    // With `-Zmir-sroa-args`, arguments are flattened like other locals, and their fragments are
+    // copied out of them at the start of the entry block, which must not be a loop header.
+    let flatten_args = tcx.sess.opts.unstable_opts.mir_sroa_args
+        && body.basic_blocks.predecessors()[START_BLOCK].is_empty();
+    if !flatten_args {
+        for arg in body.args_iter() {
+            reasons[arg] = Some(EscapeReason::ArgOrReturn);
+        }
+    }
     for (local, decl) in body.local_decls().iter_enumerated() {
-        if excluded.contains(local) || is_excluded_ty(decl.ty) {
-            set.insert(local);
+        if reasons[local].is_none() {
+            reasons[local] = excluded_ty_reason(decl.ty);
         }
     }
-    let mut visitor = EscapeVisitor { set };
+    let mut visitor = EscapeVisitor {
+        reasons,
+        flatten_return_place,
+        split_assigned: BitSet::new_empty(body.local_decls.len()),
+        whole_result_assigned: IndexVec::from_elem(None, &body.local_decls),
+    };
     visitor.visit_body(body);
-    return visitor.set;
+    // A local which is only assigned results that need a whole temporary would get one for each
+    // of them, and nothing would be left to split.
+    let mut reasons = visitor.reasons;
+    for (local, location) in visitor.whole_result_assigned.iter_enumerated() {
+        if let Some(location) = *location
+            && !visitor.split_assigned.contains(local)
+        {
+            reasons[local].get_or_insert(EscapeReason::WholeResult(location));
+        }
+    }
+    // The excluded locals are only reported as such when their uses don't say more, like the
+    // borrow that `excluded_locals` found.
+    for local in excluded.iter() {
+        reasons[local].get_or_insert(EscapeReason::Excluded);
+    }
+    return reasons;
 
     struct EscapeVisitor {
-        set: BitSet<Local>,
+        reasons: IndexVec<Local, Option<EscapeReason>>,
+        flatten_return_place: bool,
+        /// The locals assigned as a whole by an rvalue which is split into their fragments.
+        split_assigned: BitSet<Local>,
+        /// The first assignment of each local by an rvalue which needs a whole temporary.
+        whole_result_assigned: IndexVec<Local, Option<Location>>,
     }
 
     impl<'tcx> Visitor<'tcx> for EscapeVisitor {
-        fn visit_local(&mut self, local: Local, _: PlaceContext, _: Location) {
-            self.set.insert(local);
+        fn visit_local(&mut self, local: Local, context: PlaceContext, location: Location) {
+            // Only keep the first reason we find.
+            self.reasons[local].get_or_insert(EscapeReason::from_context(context, location));
         }
 
         fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
-            // Mirror the implementation in PreFlattenVisitor.
-            if let &[PlaceElem::Field(..), ..] = &place.projection[..] {
-                return;
+            // Mirror the implementation in `ReplacementMap::replace_place`.
+            match &place.projection[..] {
+                &[PlaceElem::Field(..) | PlaceElem::ConstantIndex { from_end: false, .. }, ..] => {
+                    return;
+                }
+                &[PlaceElem::Downcast(_, variant), PlaceElem::Field(..), ..]
+                    if variant == FIRST_VARIANT =>
+                {
+                    return;
+                }
+                _ => {}
             }
             self.super_place(place, context, location);
         }
@@ -124,10 +490,20 @@ fn visit_assign(
             rvalue: &Rvalue<'tcx>,
             location: Location,
         ) {
-            if lvalue.as_local().is_some() {
+            if let Some(local) = lvalue.as_local() {
                 match rvalue {
-                    // Aggregate assignments are expanded in run_pass.
-                    Rvalue::Aggregate(..) | Rvalue::Use(..) => {
+                    // Aggregate and repeat assignments are expanded in run_pass.
+                    Rvalue::Aggregate(..)
+                    | Rvalue::Repeat(..)
+                    | Rvalue::Use(..)
+                    | Rvalue::CopyForDeref(..) => {
+                        self.split_assigned.insert(local);
+                        self.visit_rvalue(rvalue, location);
+                        return;
+                    }
+                    // Checked operations and transmutes are computed into a temporary in run_pass.
+                    rvalue if needs_whole_temp(rvalue) => {
+                        self.whole_result_assigned[local].get_or_insert(location);
                         self.visit_rvalue(rvalue, location);
                         return;
                     }
@@ -140,46 +516,120 @@ fn visit_assign(
         fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
             match statement.kind {
                 // Storage statements are expanded in run_pass.
-                StatementKind::StorageLive(..)
-                | StatementKind::StorageDead(..)
-                | StatementKind::Deinit(..) => return,
+                StatementKind::StorageLive(..) | StatementKind::StorageDead(..) => return,
+                // `Deinit` of a whole local is expanded in run_pass. `Deinit` of a field applies
+                // to its fragment, and any other place is visited as usual.
+                StatementKind::Deinit(box place) if place.as_local().is_some() => return,
+                // Only single-variant enums are flattened, so setting the discriminant of a whole
+                // local is a no-op for them. It is removed in run_pass.
+                StatementKind::SetDiscriminant { box place, .. } if place.as_local().is_some() => {
+                    return;
+                }
                 _ => self.super_statement(statement, location),
             }
         }
 
+        fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+            // `return` reads the return place, which is reassembled in run_pass just before.
+            // Any other read of the whole return place still makes it escape.
+            if self.flatten_return_place
+                && let TerminatorKind::Return = terminator.kind
+            {
+                return;
+            }
+            self.super_terminator(terminator, location)
+        }
+
         // We ignore anything that happens in debuginfo, since we expand it using
         // `VarDebugInfoFragment`.
         fn visit_var_debug_info(&mut self, _: &VarDebugInfo<'tcx>) {}
     }
 }
 
+/// Arrays longer than this are not flattened, as each element gets its own local.
+const MAX_FLATTENED_ARRAY_LEN: u64 = 16;
+
+/// A "new" local replacing a part of an "old" local.
+#[derive(Copy, Clone, Debug)]
+struct Fragment<'tcx> {
+    /// For single-variant enums, the `Downcast` to that variant which comes before `elem`.
+    downcast: Option<PlaceElem<'tcx>>,
+    /// Projection from the old local to this fragment: a `Field`, or a `ConstantIndex` for arrays.
+    elem: PlaceElem<'tcx>,
+    local: Local,
+}
+
+impl<'tcx> Fragment<'tcx> {
+    /// The place of this fragment in `place`, a place of the type of the old local.
+    fn project(&self, tcx: TyCtxt<'tcx>, place: Place<'tcx>) -> Place<'tcx> {
+        let place = match self.downcast {
+            Some(downcast) => tcx.mk_place_elem(place, downcast),
+            None => place,
+        };
+        tcx.mk_place_elem(place, self.elem)
+    }
+}
+
 #[derive(Default, Debug)]
 struct ReplacementMap<'tcx> {
     /// Pre-computed list of all "new" locals for each "old" local. This is used to expand storage
     /// and deinit statement and debuginfo.
-    fragments: IndexVec<Local, Option<IndexVec<FieldIdx, Option<(Ty<'tcx>, Local)>>>>,
+    ///
+    /// Fragments of arrays are indexed by element offset.
+    fragments: IndexVec<Local, Option<IndexVec<FieldIdx, Option<Fragment<'tcx>>>>>,
+    /// With `-Zmir-sroa-partial`, the fields which stay in each partially flattened "old" local
+    /// because they are borrowed, with the projection to each of them. The old local is kept.
+    kept: IndexVec<Local, Vec<(FieldIdx, PlaceElem<'tcx>)>>,
 }
 
 impl<'tcx> ReplacementMap<'tcx> {
     fn replace_place(&self, tcx: TyCtxt<'tcx>, place: PlaceRef<'tcx>) -> Option<Place<'tcx>> {
-        let &[PlaceElem::Field(f, _), ref rest @ ..] = place.projection else {
-            return None;
+        let (index, rest) = match place.projection {
+            &[PlaceElem::Field(f, _), ref rest @ ..] => (f, rest),
+            &[PlaceElem::Downcast(_, variant), PlaceElem::Field(f, _), ref rest @ ..]
+                if variant == FIRST_VARIANT =>
+            {
+                (f, rest)
+            }
+            &[PlaceElem::ConstantIndex { offset, from_end: false, .. }, ref rest @ ..] => {
+                (FieldIdx::from_usize(offset as usize), rest)
+            }
+            _ => return None,
         };
         let fields = self.fragments[place.local].as_ref()?;
-        let (_, new_local) = fields[f]?;
-        Some(Place { local: new_local, projection: tcx.mk_place_elems(rest) })
+        let fragment = (*fields.get(index)?)?;
+        Some(Place { local: fragment.local, projection: tcx.mk_place_elems(rest) })
     }
 
     fn place_fragments(
         &self,
         place: Place<'tcx>,
-    ) -> Option<impl Iterator<Item = (FieldIdx, Ty<'tcx>, Local)> + '_> {
+    ) -> Option<impl Iterator<Item = Fragment<'tcx>> + '_> {
         let local = place.as_local()?;
         let fields = self.fragments[local].as_ref()?;
-        Some(fields.iter_enumerated().filter_map(|(field, &opt_ty_local)| {
-            let (ty, local) = opt_ty_local?;
-            Some((field, ty, local))
-        }))
+        Some(fields.iter().filter_map(|&fragment| fragment))
+    }
+
+    /// The fields which stay in `place` if it is a partially flattened local.
+    fn kept_fields(&self, place: Place<'tcx>) -> &[(FieldIdx, PlaceElem<'tcx>)] {
+        match place.as_local() {
+            Some(local) => &self.kept[local],
+            None => &[],
+        }
+    }
+
+    fn is_partially_flattened(&self, place: Place<'tcx>) -> bool {
+        !self.kept_fields(place).is_empty()
+    }
+
+    fn partially_flattened_locals(&self) -> BitSet<Local> {
+        let mut set = BitSet::new_empty(self.kept.len());
+        for (local, kept) in self.kept.iter_enumerated() {
+            if !kept.is_empty() {
+                set.insert(local);
+            }
+        }
+        set
     }
 }
 
@@ -187,13 +637,42 @@ fn place_fragments(
 ///
 /// For each eligible place, we assign a new local to each accessed field.
 /// The replacement will be done later in `ReplacementVisitor`.
+///
+/// The `borrowed` fields of a local, from `borrowed_fields`, get no new local: they stay in the
+/// old local, which is then only partially flattened.
 fn compute_flattening<'tcx>(
     tcx: TyCtxt<'tcx>,
     param_env: ty::ParamEnv<'tcx>,
     body: &mut Body<'tcx>,
     escaping: BitSet<Local>,
+    borrowed: &IndexVec<Local, Vec<FieldIdx>>,
+    max_locals: Option<usize>,
 ) -> ReplacementMap<'tcx> {
     let mut fragments = IndexVec::from_elem(None, &body.local_decls);
+    let mut kept = IndexVec::from_elem(Vec::new(), &body.local_decls);
+
+    // With `-Zmir-sroa-skip-trivial`, leave alone the aggregates with a single field whose only
+    // read moves them whole: replacing them by that field is pure churn, it enables nothing
+    // downstream. A read of the field itself still gets the aggregate flattened.
+    let moved_once =
+        tcx.sess.opts.unstable_opts.mir_sroa_skip_trivial.then(|| moved_whole_once(body));
+    let is_trivial = |local: Local, field_count: usize| {
+        field_count == 1 && moved_once.as_ref().is_some_and(|moved| moved.contains(local))
+    };
+    // Aggregates which would take the body over `max_locals` are left alone.
+    let fits = |local_count: usize, field_count: usize| {
+        !max_locals.is_some_and(|max| local_count + field_count > max)
+    };
+    // With `-Zmir-sroa-max-fragments`, aggregates which would be split into more fragments than
+    // that are left alone.
+    let max_fragments = tcx.sess.opts.unstable_opts.mir_sroa_max_fragments;
+    let too_many = |local: Local, field_count: usize| {
+        let too_many = max_fragments.is_some_and(|max| field_count > max);
+        if too_many {
+            debug!(?local, field_count, "too many fragments");
+        }
+        too_many
+    };
 
     for local in body.local_decls.indices() {
         if escaping.contains(local) {
@@ -201,17 +680,119 @@ fn compute_flattening<'tcx>(
         }
         let decl = body.local_decls[local].clone();
         let ty = decl.ty;
-        iter_fields(ty, tcx, param_env, |variant, field, field_ty| {
-            if variant.is_some() {
-                // Downcasts are currently not supported.
-                return;
+        let borrowed = borrowed.get(local).map_or(&[][..], Vec::as_slice);
+        // The return place is reassembled from all of its fields, none can stay in it.
+        if local == RETURN_PLACE && !borrowed.is_empty() {
+            continue;
+        }
+        if let ty::Array(elem_ty, len) = *ty.kind() {
+            let Some(len) = len.try_eval_target_usize(tcx, param_env) else {
+                continue;
             };
+            let flattened = len as usize - borrowed.len();
+            if len > MAX_FLATTENED_ARRAY_LEN
+                || flattened == 0
+                || is_trivial(local, flattened)
+                || too_many(local, flattened)
+                || !fits(body.local_decls.len(), flattened)
+            {
+                continue;
+            }
+            for offset in 0..len {
+                let index = FieldIdx::from_usize(offset as usize);
+                let elem = PlaceElem::ConstantIndex { offset, min_length: len, from_end: false };
+                if borrowed.contains(&index) {
+                    kept[local].push((index, elem));
+                    continue;
+                }
+                let new_local =
+                    body.local_decls.push(LocalDecl { ty: elem_ty, user_ty: None, ..decl.clone() });
+                fragments
+                    .get_or_insert_with(local, IndexVec::new)
+                    .insert(index, Fragment { downcast: None, elem, local: new_local });
+            }
+            continue;
+        }
+        let mut fields = Vec::new();
+        iter_fields(ty, tcx, param_env, |variant, field, field_ty| {
+            // Enums which get here have a single variant, see `escape_reasons`.
+            if variant.map_or(true, |variant| variant == FIRST_VARIANT) {
+                fields.push((field, field_ty));
+            }
+        });
+        let downcast = match ty.kind() {
+            ty::Adt(def, _) if def.is_enum() => {
+                Some(PlaceElem::Downcast(Some(def.variant(FIRST_VARIANT).name), FIRST_VARIANT))
+            }
+            _ => None,
+        };
+        // Only fields of structs and tuples can be borrowed without excluding the whole local, so
+        // there is never a `downcast` to keep along with the borrowed fields.
+        let flattened = fields.len() - borrowed.len();
+        if flattened == 0
+            || is_trivial(local, flattened)
+            || too_many(local, flattened)
+            || !fits(body.local_decls.len(), flattened)
+        {
+            continue;
+        }
+        for (field, field_ty) in fields {
+            let elem = PlaceElem::Field(field, field_ty);
+            if borrowed.contains(&field) {
+                kept[local].push((field, elem));
+                continue;
+            }
             let new_local =
                 body.local_decls.push(LocalDecl { ty: field_ty, user_ty: None, ..decl.clone() });
-            fragments.get_or_insert_with(local, IndexVec::new).insert(field, (field_ty, new_local));
-        });
+            fragments
+                .get_or_insert_with(local, IndexVec::new)
+                .insert(field, Fragment { downcast, elem, local: new_local });
+        }
     }
-    ReplacementMap { fragments }
+    // `return` reads the whole return place, so it is reassembled from a fragment of each field.
+    assert!(kept[RETURN_PLACE].is_empty(), "the return place is partially flattened");
+    ReplacementMap { fragments, kept }
+}
+
+/// The locals whose only read is a `move` of the whole local. Reads through a projection, like
+/// `_1.0`, and copies don't count as such.
+fn moved_whole_once(body: &Body<'_>) -> BitSet<Local> {
+    struct ReadCounter {
+        /// The places where each local is read, as a whole or through a projection.
+        reads: IndexVec<Local, u32>,
+        /// The `move` operands of each whole local.
+        whole_moves: IndexVec<Local, u32>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for ReadCounter {
+        fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
+            if let Operand::Move(place) = operand
+                && let Some(local) = place.as_local()
+            {
+                self.whole_moves[local] += 1;
+            }
+            self.super_operand(operand, location);
+        }
+
+        fn visit_local(&mut self, local: Local, context: PlaceContext, _location: Location) {
+            if context.is_use() && !context.is_mutating_use() {
+                self.reads[local] += 1;
+            }
+        }
+    }
+
+    let mut counter = ReadCounter {
+        reads: IndexVec::from_elem(0, &body.local_decls),
+        whole_moves: IndexVec::from_elem(0, &body.local_decls),
+    };
+    counter.visit_body(body);
+    let mut moved_once = BitSet::new_empty(body.local_decls.len());
+    for (local, &reads) in counter.reads.iter_enumerated() {
+        if reads == 1 && counter.whole_moves[local] == 1 {
+            moved_once.insert(local);
+        }
+    }
+    moved_once
 }
 
 /// Perform the replacement computed by `compute_flattening`.
@@ -219,16 +800,22 @@ fn replace_flattened_locals<'tcx>(
     tcx: TyCtxt<'tcx>,
     body: &mut Body<'tcx>,
     replacements: ReplacementMap<'tcx>,
-) -> BitSet<Local> {
+    stats: &mut SroaStats,
+) -> (BitSet<Local>, Vec<Local>) {
     let mut all_dead_locals = BitSet::new_empty(replacements.fragments.len());
-    for (local, replacements) in replacements.fragments.iter_enumerated() {
-        if replacements.is_some() {
-            all_dead_locals.insert(local);
+    let mut any_replaced = false;
+    for (local, fragments) in replacements.fragments.iter_enumerated() {
+        if fragments.is_some() {
+            any_replaced = true;
+            // Partially flattened locals still hold their borrowed fields.
+            if replacements.kept[local].is_empty() {
+                all_dead_locals.insert(local);
+            }
         }
     }
     debug!(?all_dead_locals);
-    if all_dead_locals.is_empty() {
-        return all_dead_locals;
+    if !any_replaced {
+        return (all_dead_locals, Vec::new());
     }
 
     let mut visitor = ReplacementVisitor {
@@ -236,7 +823,10 @@ fn replace_flattened_locals<'tcx>(
         local_decls: &body.local_decls,
         replacements: &replacements,
         all_dead_locals,
+        constant_locals: BitSet::new_empty(body.local_decls.len()),
         patch: MirPatch::new(body),
+        expanded_aggregates: 0,
+        whole_temps: Vec::new(),
     };
     for (bb, data) in body.basic_blocks.as_mut_preserves_cfg().iter_enumerated_mut() {
         visitor.visit_basic_block_data(bb, data);
@@ -248,9 +838,105 @@ fn replace_flattened_locals<'tcx>(
         visitor.visit_user_type_annotation(index, annotation);
     }
     visitor.expand_var_debug_info(&mut body.var_debug_info);
-    let ReplacementVisitor { patch, all_dead_locals, .. } = visitor;
+    let ReplacementVisitor {
+        patch,
+        all_dead_locals,
+        constant_locals,
+        expanded_aggregates,
+        whole_temps,
+        ..
+    } = visitor;
+    stats.expanded_aggregates += expanded_aggregates;
+    patch.apply(body);
+    if tcx.sess.opts.unstable_opts.validate_mir {
+        // A flattened return place is reassembled before each `return`, which then reads it, so
+        // those are legitimate uses. So are the reads of locals assigned a constant, which are
+        // kept until ConstProp folds their fragments.
+        let mut dead_locals = all_dead_locals.clone();
+        dead_locals.remove(RETURN_PLACE);
+        for local in constant_locals.iter() {
+            dead_locals.remove(local);
+        }
+        check_no_dead_locals(tcx, body, &dead_locals);
+    }
+    copy_out_flattened_args(tcx, body, &replacements);
+    (all_dead_locals, whole_temps)
+}
+
+/// The ABI still passes flattened arguments whole, so move their fields into the fragments at the
+/// start of the body. These are the only uses of the arguments left, which is why this comes
+/// after `check_no_dead_locals`.
+fn copy_out_flattened_args<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    replacements: &ReplacementMap<'tcx>,
+) {
+    let mut patch = MirPatch::new(body);
+    for arg in body.args_iter() {
+        let Some(fragments) = &replacements.fragments[arg] else { continue };
+        let source_info = body.local_decls[arg].source_info;
+        for fragment in fragments.iter().flatten() {
+            let field = fragment.project(tcx, Place::from(arg));
+            let rvalue = Rvalue::Use(Operand::Move(field));
+            patch.add_statement_with_source_info(
+                Location::START,
+                source_info,
+                StatementKind::Assign(Box::new((Place::from(fragment.local), rvalue))),
+            );
+        }
+    }
     patch.apply(body);
-    all_dead_locals
+}
+
+/// Check that no place in `body` still refers to a replaced local. `ReplacementVisitor` only
+/// asserts this on the places it visits, which excludes the statements added by the patch.
+fn check_no_dead_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    all_dead_locals: &BitSet<Local>,
+) {
+    struct DeadLocalFinder<'a, 'tcx> {
+        all_dead_locals: &'a BitSet<Local>,
+        uses: Vec<(Location, Place<'tcx>)>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for DeadLocalFinder<'_, 'tcx> {
+        fn visit_place(&mut self, place: &Place<'tcx>, _: PlaceContext, location: Location) {
+            let mut index_locals = place.projection.iter().filter_map(|elem| match elem {
+                PlaceElem::Index(local) => Some(local),
+                _ => None,
+            });
+            if self.all_dead_locals.contains(place.local)
+                || index_locals.any(|local| self.all_dead_locals.contains(local))
+            {
+                self.uses.push((location, *place));
+            }
+        }
+
+        fn visit_local(&mut self, local: Local, _: PlaceContext, location: Location) {
+            // Places are handled by `visit_place`, this only sees bare locals such as the ones
+            // in `StorageLive` and `StorageDead`.
+            if self.all_dead_locals.contains(local) {
+                self.uses.push((location, local.into()));
+            }
+        }
+    }
+
+    let mut finder = DeadLocalFinder { all_dead_locals, uses: Vec::new() };
+    finder.visit_body(body);
+    let Some(&(location, _)) = finder.uses.first() else { return };
+    let uses: String = finder
+        .uses
+        .iter()
+        .map(|(location, place)| format!("\n    {place:?} at {location:?}"))
+        .collect();
+    tcx.dcx().span_bug(
+        body.source_info(location).span,
+        format!(
+            "broken MIR in {:?} (after SROA):\nreplaced locals are still used:{uses}",
+            body.source.instance,
+        ),
+    );
 }
 
 struct ReplacementVisitor<'tcx, 'll> {
@@ -261,7 +947,15 @@ struct ReplacementVisitor<'tcx, 'll> {
     replacements: &'ll ReplacementMap<'tcx>,
     /// This is used to check that we are not leaving references to replaced locals behind.
     all_dead_locals: BitSet<Local>,
+    /// The replaced locals which keep their assignment of a constant, and are read by their
+    /// fragments.
+    constant_locals: BitSet<Local>,
     patch: MirPatch<'tcx>,
+    /// How many aggregate assignments were split, for `-Zmir-sroa-stats`.
+    expanded_aggregates: usize,
+    /// The temporaries holding the results of checked operations and transmutes, which must stay
+    /// whole.
+    whole_temps: Vec<Local>,
 }
 
 impl<'tcx> ReplacementVisitor<'tcx, '_> {
@@ -282,17 +976,28 @@ fn expand_var_debug_info(&mut self, var_debug_info: &mut Vec<VarDebugInfo<'tcx>>
                 return vec![var_debug_info];
             };
 
+            let place = *place;
             let ty = place.ty(self.local_decls, self.tcx).ty;
+            // The borrowed fields of a partially flattened local are described where they stay.
+            let tcx = self.tcx;
+            let kept = self
+                .replacements
+                .kept_fields(place)
+                .iter()
+                .map(|&(_, elem)| (None, elem, tcx.mk_place_elem(place, elem)));
 
             parts
-                .map(|(field, field_ty, replacement_local)| {
+                .map(|fragment| (fragment.downcast, fragment.elem, fragment.local.into()))
+                .chain(kept)
+                .map(|(downcast, elem, value)| {
                     let mut var_debug_info = var_debug_info.clone();
                     let composite = var_debug_info.composite.get_or_insert_with(|| {
                         Box::new(VarDebugInfoFragment { ty, projection: Vec::new() })
                     });
-                    composite.projection.push(PlaceElem::Field(field, field_ty));
+                    composite.projection.extend(downcast);
+                    composite.projection.push(elem);
 
-                    var_debug_info.value = VarDebugInfoContents::Place(replacement_local.into());
+                    var_debug_info.value = VarDebugInfoContents::Place(value);
                     var_debug_info
                 })
                 .collect()
@@ -317,34 +1022,65 @@ fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, locati
     fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
         match statement.kind {
             // Duplicate storage and deinit statements, as they pretty much apply to all fields.
+            // Partially flattened locals keep theirs, for the fields which stay in them.
             StatementKind::StorageLive(l) => {
                 if let Some(final_locals) = self.replacements.place_fragments(l.into()) {
-                    for (_, _, fl) in final_locals {
-                        self.patch.add_statement(location, StatementKind::StorageLive(fl));
+                    for fragment in final_locals {
+                        self.patch
+                            .add_statement(location, StatementKind::StorageLive(fragment.local));
+                    }
+                    if !self.replacements.is_partially_flattened(l.into()) {
+                        statement.make_nop();
                     }
-                    statement.make_nop();
                 }
                 return;
             }
             StatementKind::StorageDead(l) => {
                 if let Some(final_locals) = self.replacements.place_fragments(l.into()) {
-                    for (_, _, fl) in final_locals {
-                        self.patch.add_statement(location, StatementKind::StorageDead(fl));
+                    for fragment in final_locals {
+                        self.patch
+                            .add_statement(location, StatementKind::StorageDead(fragment.local));
+                    }
+                    if !self.replacements.is_partially_flattened(l.into()) {
+                        statement.make_nop();
                     }
-                    statement.make_nop();
                 }
                 return;
             }
-            StatementKind::Deinit(box place) => {
-                if let Some(final_locals) = self.replacements.place_fragments(place) {
-                    for (_, _, fl) in final_locals {
-                        self.patch
-                            .add_statement(location, StatementKind::Deinit(Box::new(fl.into())));
+            // Flattened enums have a single variant, so there is no discriminant to set.
+            StatementKind::SetDiscriminant { box place, variant_index } => {
+                if self.replacements.place_fragments(place).is_some() {
+                    if variant_index != FIRST_VARIANT {
+                        span_bug!(
+                            statement.source_info.span,
+                            "set the discriminant of flattened {place:?} to {variant_index:?}, \
+                             but it has a single variant",
+                        );
                     }
                     statement.make_nop();
                     return;
                 }
             }
+            StatementKind::Deinit(box ref mut place) => {
+                // `Deinit(a.1)` only deinitializes the fragment `a_1`.
+                if let Some(repl) = self.replacements.replace_place(self.tcx, place.as_ref()) {
+                    *place = repl;
+                    return;
+                }
+                let place = *place;
+                if let Some(final_locals) = self.replacements.place_fragments(place) {
+                    for fragment in final_locals {
+                        self.patch.add_statement(
+                            location,
+                            StatementKind::Deinit(Box::new(fragment.local.into())),
+                        );
+                    }
+                    if !self.replacements.is_partially_flattened(place) {
+                        statement.make_nop();
+                    }
+                    return;
+                }
+            }
 
             // We have `a = Struct { 0: x, 1: y, .. }`.
             // We replace it by
@@ -353,23 +1089,102 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
             // a_1 = y
             // ...
             // ```
+            // The fields which stay in a partially flattened local are assigned in place, as in
+            // `a.2 = z`.
             StatementKind::Assign(box (place, Rvalue::Aggregate(_, ref mut operands))) => {
                 if let Some(local) = place.as_local()
                     && let Some(final_locals) = &self.replacements.fragments[local]
                 {
+                    let kept = self.replacements.kept_fields(place);
                     // This is ok as we delete the statement later.
                     let operands = std::mem::take(operands);
-                    for (&opt_ty_local, mut operand) in final_locals.iter().zip(operands) {
-                        if let Some((_, new_local)) = opt_ty_local {
-                            // Replace mentions of SROA'd locals that appear in the operand.
-                            self.visit_operand(&mut operand, location);
-
-                            let rvalue = Rvalue::Use(operand);
-                            self.patch.add_statement(
-                                location,
-                                StatementKind::Assign(Box::new((new_local.into(), rvalue))),
-                            );
-                        }
+                    for (index, mut operand) in operands.into_iter_enumerated() {
+                        let lhs = match final_locals.get(index) {
+                            Some(&Some(Fragment { local: new_local, .. })) => new_local.into(),
+                            _ => match kept.iter().find(|&&(field, _)| field == index) {
+                                Some(&(_, elem)) => self.tcx.mk_place_elem(place, elem),
+                                None => continue,
+                            },
+                        };
+                        // Replace mentions of SROA'd locals that appear in the operand.
+                        self.visit_operand(&mut operand, location);
+
+                        let rvalue = Rvalue::Use(operand);
+                        self.patch.add_statement(
+                            location,
+                            StatementKind::Assign(Box::new((lhs, rvalue))),
+                        );
+                    }
+                    self.expanded_aggregates += 1;
+                    statement.make_nop();
+                    return;
+                }
+            }
+
+            // We have `a = [x; N]`.
+            // We replace it by
+            // ```
+            // a_0 = x
+            // a_1 = x
+            // ...
+            // ```
+            StatementKind::Assign(box (place, Rvalue::Repeat(ref operand, _))) => {
+                if let Some(final_locals) = self.replacements.place_fragments(place) {
+                    let kept = self.replacements.kept_fields(place).iter();
+                    let lhs: Vec<Place<'tcx>> = final_locals
+                        .map(|fragment| fragment.local.into())
+                        .chain(kept.map(|&(_, elem)| self.tcx.mk_place_elem(place, elem)))
+                        .collect();
+                    let mut operand = operand.clone();
+                    // Replace mentions of SROA'd locals that appear in the operand.
+                    self.visit_operand(&mut operand, location);
+                    for (i, &lhs_place) in lhs.iter().enumerate() {
+                        // Only the last copy may move out of the operand.
+                        let operand = match operand {
+                            Operand::Move(rplace) if i + 1 < lhs.len() => Operand::Copy(rplace),
+                            ref operand => operand.clone(),
+                        };
+                        self.patch.add_statement(
+                            location,
+                            StatementKind::Assign(Box::new((lhs_place, Rvalue::Use(operand)))),
+                        );
+                    }
+                    statement.make_nop();
+                    return;
+                }
+            }
+
+            // We have `a = CheckedOp(x, y)` or `a = x as A (Transmute)`.
+            // We replace it by
+            // ```
+            // tmp = CheckedOp(x, y)
+            // a_0 = tmp.0
+            // a_1 = tmp.1
+            // ```
+            // `tmp` is never flattened, or each round would make a new one.
+            StatementKind::Assign(box (place, ref rvalue)) if needs_whole_temp(rvalue) => {
+                if self.replacements.place_fragments(place).is_some() {
+                    let mut rvalue = rvalue.clone();
+                    // Replace mentions of SROA'd locals that appear in the operands.
+                    self.visit_rvalue(&mut rvalue, location);
+                    let ty = place.ty(self.local_decls, self.tcx).ty;
+                    let temp = self.patch.new_temp(ty, statement.source_info.span);
+                    self.whole_temps.push(temp);
+                    self.patch.add_assign(location, temp.into(), rvalue);
+                    let final_locals = self.replacements.place_fragments(place).unwrap();
+                    let kept = self.replacements.kept_fields(place).iter().map(|&(_, elem)| {
+                        (
+                            self.tcx.mk_place_elem(place, elem),
+                            self.tcx.mk_place_elem(temp.into(), elem),
+                        )
+                    });
+                    let parts = final_locals
+                        .map(|fragment| {
+                            (fragment.local.into(), fragment.project(self.tcx, temp.into()))
+                        })
+                        .chain(kept);
+                    for (lplace, rplace) in parts {
+                        self.patch.add_assign(location, lplace, Rvalue::Use(Operand::Copy(rplace)));
                     }
                     statement.make_nop();
                     return;
@@ -386,37 +1201,53 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
             // ConstProp will pick up the pieces and replace them by actual constants.
             StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(_)))) => {
                 if let Some(final_locals) = self.replacements.place_fragments(place) {
-                    // Put the deaggregated statements *after* the original one.
+                    // Put the deaggregated statements *after* the original one, but keep its
+                    // span rather than taking the one of the statement that follows.
+                    let source_info = statement.source_info;
                     let location = location.successor_within_block();
-                    for (field, ty, new_local) in final_locals {
-                        let rplace = self.tcx.mk_place_field(place, field, ty);
+                    for fragment in final_locals {
+                        let rplace = fragment.project(self.tcx, place);
                         let rvalue = Rvalue::Use(Operand::Move(rplace));
-                        self.patch.add_statement(
+                        self.patch.add_statement_with_source_info(
                             location,
-                            StatementKind::Assign(Box::new((new_local.into(), rvalue))),
+                            source_info,
+                            StatementKind::Assign(Box::new((fragment.local.into(), rvalue))),
                         );
                     }
                     // We still need `place.local` to exist, so don't make it nop.
+                    self.constant_locals.insert(place.local);
                     return;
                 }
             }
 
-            // We have `a = move? place`
+            // We have `a = move? place` or `a = deref_copy place`
             // We replace it by
             // ```
             // a_0 = move? place.0
             // a_1 = move? place.1
             // ...
             // ```
-            StatementKind::Assign(box (lhs, Rvalue::Use(ref op))) => {
-                let (rplace, copy) = match *op {
-                    Operand::Copy(rplace) => (rplace, true),
-                    Operand::Move(rplace) => (rplace, false),
-                    Operand::Constant(_) => bug!(),
+            // A `deref_copy` is a plain copy, so its fragments are copied.
+            StatementKind::Assign(box (
+                lhs,
+                ref rvalue @ (Rvalue::Use(_) | Rvalue::CopyForDeref(_)),
+            )) => {
+                let (rplace, copy) = match *rvalue {
+                    Rvalue::Use(Operand::Copy(rplace)) | Rvalue::CopyForDeref(rplace) => {
+                        (rplace, true)
+                    }
+                    Rvalue::Use(Operand::Move(rplace)) => (rplace, false),
+                    _ => bug!(),
                 };
                 if let Some(final_locals) = self.replacements.place_fragments(lhs) {
-                    for (field, ty, new_local) in final_locals {
-                        let rplace = self.tcx.mk_place_field(rplace, field, ty);
+                    // The fields which stay in a partially flattened local are assigned in place.
+                    let kept = self.replacements.kept_fields(lhs).iter().map(|&(_, elem)| {
+                        (self.tcx.mk_place_elem(lhs, elem), self.tcx.mk_place_elem(rplace, elem))
+                    });
+                    let parts = final_locals
+                        .map(|fragment| (fragment.local.into(), fragment.project(self.tcx, rplace)))
+                        .chain(kept);
+                    for (lplace, rplace) in parts {
                         debug!(?rplace);
                         let rplace = self
                             .replacements
@@ -430,7 +1261,7 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
                         };
                         self.patch.add_statement(
                             location,
-                            StatementKind::Assign(Box::new((new_local.into(), rvalue))),
+                            StatementKind::Assign(Box::new((lplace, rvalue))),
                         );
                     }
                     statement.make_nop();
@@ -443,7 +1274,46 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
         self.super_statement(statement, location)
     }
 
+    fn visit_terminator(&mut self, terminator: &mut Terminator<'tcx>, location: Location) {
+        // We have a flattened return place. We reassemble it for `return` to read:
+        // ```
+        // _0 = Struct { 0: _0_0, 1: _0_1, .. }
+        // return
+        // ```
+        if let TerminatorKind::Return = terminator.kind
+            && let Some(final_locals) = &self.replacements.fragments[RETURN_PLACE]
+        {
+            let operands = final_locals
+                .iter()
+                .map(|fragment| {
+                    // `compute_flattening` never keeps a field in the return place.
+                    let fragment = fragment.expect("the return place is flattened whole");
+                    Operand::Move(fragment.local.into())
+                })
+                .collect();
+            let kind = aggregate_kind(self.local_decls[RETURN_PLACE].ty);
+            self.patch.add_assign(
+                location,
+                RETURN_PLACE.into(),
+                Rvalue::Aggregate(Box::new(kind), operands),
+            );
+            return;
+        }
+        self.super_terminator(terminator, location)
+    }
+
     fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
         assert!(!self.all_dead_locals.contains(*local));
     }
 }
+
+/// The aggregate building a value of `ty` from its fields, in the order of its fragments.
+fn aggregate_kind<'tcx>(ty: Ty<'tcx>) -> AggregateKind<'tcx> {
+    match *ty.kind() {
+        ty::Tuple(..) => AggregateKind::Tuple,
+        ty::Array(elem_ty, _) => AggregateKind::Array(elem_ty),
+        // Enums which get here have a single variant, see `escape_reasons`.
+        ty::Adt(def, args) => AggregateKind::Adt(def.did(), FIRST_VARIANT, args, None, None),
+        _ => bug!("cannot reassemble a flattened value of type {ty:?}"),
+    }
+}
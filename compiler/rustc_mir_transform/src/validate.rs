@@ -794,7 +794,9 @@ fn visit_projection_elem(
 
     fn visit_var_debug_info(&mut self, debuginfo: &VarDebugInfo<'tcx>) {
         if let Some(box VarDebugInfoFragment { ty, ref projection }) = debuginfo.composite {
-            if ty.is_union() || ty.is_enum() {
+            // SROA flattens enums with a single variant, like structs.
+            let multi_variant = ty.ty_adt_def().is_some_and(|def| def.variants().len() != 1);
+            if ty.is_union() || (ty.is_enum() && multi_variant) {
                 self.fail(
                     START_BLOCK.start_location(),
                     format!("invalid type {ty:?} in debuginfo for {:?}", debuginfo.name),
@@ -806,7 +808,12 @@ fn visit_var_debug_info(&mut self, debuginfo: &VarDebugInfo<'tcx>) {
                     format!("invalid empty projection in debuginfo for {:?}", debuginfo.name),
                 );
             }
-            if projection.iter().any(|p| !matches!(p, PlaceElem::Field(..))) {
+            if projection.iter().any(|p| {
+                !matches!(
+                    p,
+                    PlaceElem::Field(..) | PlaceElem::ConstantIndex { from_end: false, .. }
+                ) && !matches!(p, PlaceElem::Downcast(_, variant) if *variant == FIRST_VARIANT)
+            }) {
                 self.fail(
                     START_BLOCK.start_location(),
                     format!(
@@ -1811,6 +1811,36 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     #[rustc_lint_opt_deny_field_access("use `Session::mir_opt_level` instead of this field")]
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
+    mir_sroa: Option<bool> = (None, parse_opt_bool, [TRACKED],
+        "enable or disable scalar replacement of aggregates, which otherwise runs from \
+        `-Zmir-opt-level=2`; enabling it also runs it at `-Zmir-opt-level=1` (default: no)"),
+    mir_sroa_args: bool = (false, parse_bool, [TRACKED],
+        "let scalar replacement of aggregates split the arguments which are never borrowed, \
+        copying their fields out on entry (default: no)"),
+    mir_sroa_escape_reasons: bool = (false, parse_bool, [UNTRACKED],
+        "emit a note saying why scalar replacement of aggregates cannot split each local it \
+        leaves whole (default: no)"),
+    mir_sroa_local_growth_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "stop scalar replacement of aggregates before a body has more than this many times its \
+        original number of locals (default: no limit)"),
+    mir_sroa_max_fragments: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "do not split a local into more than this many fragments in scalar replacement of \
+        aggregates, leaving larger aggregates whole (default: no limit)"),
+    mir_sroa_partial: bool = (false, parse_bool, [TRACKED],
+        "let scalar replacement of aggregates split the fields of a local which are not borrowed \
+        when references to its other fields are taken (default: no)"),
+    mir_sroa_preserve: Vec<String> = (Vec::new(), parse_comma_list, [TRACKED],
+        "a comma-separated list of user variable names whose locals are never split by scalar \
+        replacement of aggregates (default: none)"),
+    mir_sroa_return_place: bool = (false, parse_bool, [TRACKED],
+        "let scalar replacement of aggregates split the return place, and reassemble it before \
+        each return (default: no)"),
+    mir_sroa_skip_trivial: bool = (false, parse_bool, [TRACKED],
+        "do not split aggregates with a single field whose only read moves them whole in scalar \
+        replacement of aggregates (default: no)"),
+    mir_sroa_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print, for each body, how many locals scalar replacement of aggregates split and into \
+        how many fragments (default: no)"),
     move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
         "the size at which the `large_assignments` lint starts to be emitted"),
     mutable_noalias: bool = (true, parse_bool, [TRACKED],
@@ -1605,10 +1605,12 @@
         rustc_macro_transparency,
         rustc_main,
         rustc_mir,
+        rustc_mir_sroa,
         rustc_must_implement_one_of,
         rustc_never_returns_null_ptr,
         rustc_never_type_options,
         rustc_no_mir_inline,
+        rustc_no_mir_sroa,
         rustc_nonnull_optimization_guaranteed,
         rustc_nounwind,
         rustc_object_lifetime_default,
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fmt;
+use std::fs;
 use std::io;
 use std::io::Read;
 use std::path::Path;
@@ -25,7 +26,7 @@
 use crate::core::new_dcx;
 use crate::externalfiles::ExternalHtml;
 use crate::html;
-use crate::html::markdown::IdMap;
+use crate::html::markdown::{HeadingOffset, IdMap};
 use crate::html::render::StylePath;
 use crate::html::static_files;
 use crate::opts;
@@ -64,6 +65,8 @@ pub(crate) struct Options {
     // Basic options / Options passed directly to rustc
     /// The crate root or Markdown file to load.
     pub(crate) input: Input,
+    /// The Markdown files given after `input`, rendered along with it.
+    pub(crate) more_markdown_inputs: Vec<PathBuf>,
     /// The name of the crate being documented.
     pub(crate) crate_name: Option<String>,
     /// Whether or not this is a bin crate
@@ -128,6 +131,9 @@ pub(crate) struct Options {
     pub(crate) enable_per_target_ignores: bool,
     /// Do not run doctests, compile them if should_test is active.
     pub(crate) no_run: bool,
+    /// The `[<file>:]<line>` selectors of the doctests of a Markdown file to run. All of them run
+    /// if this is empty.
+    pub(crate) markdown_test_lines: Vec<String>,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from `$sysroot/bin/rustc`.
@@ -183,6 +189,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 
         f.debug_struct("Options")
             .field("input", &self.input.source_name())
+            .field("more_markdown_inputs", &self.more_markdown_inputs)
             .field("crate_name", &self.crate_name)
             .field("bin_crate", &self.bin_crate)
             .field("proc_macro_crate", &self.proc_macro_crate)
@@ -210,6 +217,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
             .field("run_check", &self.run_check)
             .field("no_run", &self.no_run)
+            .field("markdown_test_lines", &self.markdown_test_lines)
             .field("test_builder_wrappers", &self.test_builder_wrappers)
             .field("nocapture", &self.nocapture)
             .field("scrape_examples_options", &self.scrape_examples_options)
@@ -263,11 +271,95 @@ pub(crate) struct RenderOptions {
     /// Whether to generate a table of contents on the output file when reading a standalone
     /// Markdown file.
     pub(crate) markdown_no_toc: bool,
+    /// Only include headings up to this level in the table of contents of standalone Markdown
+    /// files.
+    pub(crate) markdown_toc_depth: Option<u32>,
+    /// Whether to show the table of contents of standalone Markdown files in a sidebar next to
+    /// the content rather than above it.
+    pub(crate) markdown_toc_sidebar: bool,
+    /// Whether to also write the table of contents of standalone Markdown files to a separate
+    /// `<input>.toc.html` file next to the output file.
+    pub(crate) markdown_toc_file: bool,
+    /// Whether to write the page generated from a standalone Markdown file to stdout, with `-o -`
+    /// and `-Z unstable-options`. Without the latter, `-` is a directory like any other.
+    pub(crate) markdown_stdout: bool,
     /// Additional CSS files to link in pages generated from standalone Markdown files.
     pub(crate) markdown_css: Vec<String>,
     /// If present, playground URL to use in the "Run" button added to code samples generated from
     /// standalone Markdown files. If not present, `playground_url` is used.
     pub(crate) markdown_playground_url: Option<String>,
+    /// Whether to rewrite `http://` links to `https://` in pages generated from standalone
+    /// Markdown files.
+    pub(crate) markdown_upgrade_insecure: bool,
+    /// Whether to turn bare URLs into links in pages generated from standalone Markdown files.
+    pub(crate) markdown_autolink: bool,
+    /// Content of the `viewport` `<meta>` tag in pages generated from standalone Markdown files.
+    pub(crate) markdown_viewport: String,
+    /// Language of pages generated from standalone Markdown files, in their `<html lang>`.
+    pub(crate) markdown_lang: String,
+    /// Whether to also write an `index.html` page linking to each of the rendered Markdown files.
+    pub(crate) markdown_index: bool,
+    /// Whether to render all the Markdown files into a single page.
+    pub(crate) markdown_combine: bool,
+    /// If present, the title of the single page of `markdown_combine`, instead of the one of the
+    /// first file.
+    pub(crate) markdown_combine_title: Option<String>,
+    /// If present, the URL of the MathJax script loaded to typeset the math of pages generated
+    /// from standalone Markdown files.
+    pub(crate) markdown_math: Option<String>,
+    /// Whether to link each heading of pages generated from standalone Markdown files to the top
+    /// of the page.
+    pub(crate) markdown_back_to_top: bool,
+    /// If present, the contents of the HTML file which pages generated from standalone Markdown
+    /// files are rendered into, in place of the built-in page.
+    pub(crate) markdown_template: Option<String>,
+    /// Offset at which the headings of standalone Markdown files are rendered, so that they can
+    /// be embedded below the headings of another page.
+    pub(crate) markdown_heading_offset: HeadingOffset,
+    /// Whether to parse `{#id}` attributes on the headings of standalone Markdown files.
+    pub(crate) markdown_heading_attributes: bool,
+    /// If present, the bundled theme whose colors highlight the Rust code blocks of standalone
+    /// Markdown files.
+    pub(crate) markdown_highlight_theme: Option<String>,
+    /// Whether to emit a JSON-LD `Article` block built from the metadata of standalone Markdown
+    /// files.
+    pub(crate) markdown_json_ld: bool,
+    /// Words per minute used to estimate the reading time of standalone Markdown files, if the
+    /// estimate should be shown.
+    pub(crate) markdown_reading_time: Option<u32>,
+    /// Banner shown at the top of pages generated from standalone Markdown files. Overrides the
+    /// `banner` metadata of the file.
+    pub(crate) markdown_banner: Option<String>,
+    /// Whether the banner of `markdown_banner` is HTML rather than text to escape. The `banner`
+    /// metadata of a file is always escaped.
+    pub(crate) markdown_banner_html: bool,
+    /// Whether to embed the local images of standalone Markdown files into the page as `data:`
+    /// URIs.
+    pub(crate) markdown_inline_images: bool,
+    /// The largest image, in bytes, that `markdown_inline_images` embeds.
+    pub(crate) markdown_inline_image_limit: u64,
+    /// If present, the raw HTML tags kept in pages generated from standalone Markdown files.
+    /// Other tags are escaped.
+    pub(crate) markdown_allowed_html_tags: Option<Vec<String>>,
+    /// Whether to remove trailing whitespace from the lines of code blocks in pages generated
+    /// from standalone Markdown files.
+    pub(crate) markdown_trim_code_whitespace: bool,
+    /// If present, the Content Security Policy of pages generated from standalone Markdown files.
+    pub(crate) markdown_csp: Option<String>,
+    /// If present, a program which pages generated from standalone Markdown files are piped
+    /// through before being written.
+    pub(crate) markdown_filter: Option<String>,
+    /// Arguments to pass to the `markdown_filter` program.
+    pub(crate) markdown_filter_args: Vec<String>,
+    /// Whether to write pages generated from standalone Markdown files with `\r\n` line endings.
+    pub(crate) markdown_crlf: bool,
+    /// If present, the URL `#1234` references in pages generated from standalone Markdown files
+    /// link to, with `{}` replaced by the number.
+    pub(crate) markdown_issue_url: Option<String>,
+    /// If present, the URL of the icon of pages generated from standalone Markdown files.
+    pub(crate) markdown_favicon: Option<String>,
+    /// If present, the `theme-color` of pages generated from standalone Markdown files.
+    pub(crate) markdown_theme_color: Option<String>,
     /// Document items that have lower than `pub` visibility.
     pub(crate) document_private: bool,
     /// Document items that have `doc(hidden)`.
@@ -467,16 +559,33 @@ fn println_condition(condition: Condition) {
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
 
-        let input = if describe_lints {
-            "" // dummy, this won't be used
+        let (input, more_inputs) = if describe_lints {
+            ("", &[][..]) // dummy, this won't be used
         } else {
             match matches.free.as_slice() {
                 [] => dcx.fatal("missing file operand"),
-                [input] => input,
-                _ => dcx.fatal("too many file operands"),
+                [input, more_inputs @ ..] => (&**input, more_inputs),
             }
         };
-        let input = make_input(early_dcx, &input);
+        // Several Markdown files can be rendered at once.
+        let more_markdown_inputs: Vec<PathBuf> = more_inputs.iter().map(PathBuf::from).collect();
+        if !more_markdown_inputs.is_empty() {
+            if !std::iter::once(Path::new(input))
+                .chain(more_markdown_inputs.iter().map(|p| p.as_path()))
+                .all(is_markdown_path)
+            {
+                dcx.fatal("too many file operands");
+            }
+            if !nightly_options::is_unstable_enabled(matches) {
+                dcx.fatal(
+                    "the -Z unstable-options flag must be passed to render several Markdown files",
+                );
+            }
+            if matches.opt_present("test") {
+                dcx.fatal("cannot test several Markdown files at once");
+            }
+        }
+        let input = make_input(early_dcx, input);
 
         let externs = parse_externs(early_dcx, matches, &unstable_opts);
         let extern_html_root_urls = match parse_extern_html_roots(matches) {
@@ -536,6 +645,7 @@ fn println_condition(condition: Condition) {
 
         let should_test = matches.opt_present("test");
         let no_run = matches.opt_present("no-run");
+        let markdown_test_lines = matches.opt_strs("markdown-test-line");
 
         if !should_test && no_run {
             dcx.fatal("the `--test` flag must be passed to enable `--no-run`");
@@ -553,6 +663,8 @@ fn println_condition(condition: Condition) {
             (None, Some(output)) => output,
             (None, None) => PathBuf::from("doc"),
         };
+        let markdown_stdout =
+            output == Path::new("-") && nightly_options::is_unstable_enabled(matches);
 
         let cfgs = matches.opt_strs("cfg");
         let check_cfgs = matches.opt_strs("check-cfg");
@@ -699,8 +811,162 @@ fn println_condition(condition: Condition) {
         };
         let resource_suffix = matches.opt_str("resource-suffix").unwrap_or_default();
         let markdown_no_toc = matches.opt_present("markdown-no-toc");
+        let markdown_toc_depth = match matches.opt_str("markdown-toc-depth") {
+            None => None,
+            Some(depth) => match depth.parse::<u32>() {
+                Ok(depth @ 1..=6) => Some(depth),
+                _ => dcx.fatal(format!(
+                    "option `--markdown-toc-depth` argument must be a heading level between 1 \
+                     and 6, got `{depth}`"
+                )),
+            },
+        };
+        let markdown_toc_sidebar = matches.opt_present("markdown-toc-sidebar");
+        if markdown_toc_sidebar && markdown_no_toc {
+            dcx.fatal("option `--markdown-toc-sidebar` cannot be used with `--markdown-no-toc`");
+        }
+        let markdown_toc_file = matches.opt_present("markdown-toc-file");
+        if markdown_toc_file && markdown_no_toc {
+            dcx.fatal("option `--markdown-toc-file` cannot be used with `--markdown-no-toc`");
+        }
         let markdown_css = matches.opt_strs("markdown-css");
         let markdown_playground_url = matches.opt_str("markdown-playground-url");
+        let markdown_upgrade_insecure = matches.opt_present("markdown-upgrade-insecure");
+        let markdown_autolink = matches.opt_present("markdown-autolink");
+        let markdown_viewport = matches
+            .opt_str("markdown-viewport")
+            .unwrap_or_else(|| "width=device-width, initial-scale=1.0".to_owned());
+        if markdown_viewport.trim().is_empty() {
+            dcx.fatal("option `--markdown-viewport` argument must not be empty");
+        }
+        let markdown_index = matches.opt_present("markdown-index");
+        let markdown_combine = matches.opt_present("markdown-combine");
+        let markdown_combine_title = matches.opt_str("markdown-combine-title");
+        if markdown_combine_title.is_some() && !markdown_combine {
+            dcx.fatal("option `--markdown-combine-title` requires `--markdown-combine`");
+        }
+        let markdown_back_to_top = matches.opt_present("markdown-back-to-top");
+        let markdown_template = matches.opt_str("markdown-template").map(|path| {
+            let template = fs::read_to_string(&path).unwrap_or_else(|e| {
+                dcx.fatal(format!("cannot read `--markdown-template` file `{path}`: {e}"))
+            });
+            let missing: Vec<_> = crate::markdown::REQUIRED_TEMPLATE_PLACEHOLDERS
+                .iter()
+                .filter(|placeholder| !template.contains(**placeholder))
+                .map(|placeholder| format!("`{placeholder}`"))
+                .collect();
+            if !missing.is_empty() {
+                dcx.fatal(format!(
+                    "`--markdown-template` file `{path}` is missing the {} placeholder{}",
+                    missing.join(" and "),
+                    if missing.len() == 1 { "" } else { "s" },
+                ));
+            }
+            template
+        });
+        let markdown_heading_offset = match matches.opt_str("markdown-heading-offset").as_deref() {
+            None | Some("0") => HeadingOffset::H1,
+            Some("1") => HeadingOffset::H2,
+            Some("2") => HeadingOffset::H3,
+            Some("3") => HeadingOffset::H4,
+            Some("4") => HeadingOffset::H5,
+            Some("5") => HeadingOffset::H6,
+            Some(offset) => dcx.fatal(format!(
+                "option `--markdown-heading-offset` argument must be between 0 and 5, got \
+                 `{offset}`"
+            )),
+        };
+        let markdown_heading_attributes = matches.opt_present("markdown-heading-attributes");
+        let markdown_highlight_theme = matches.opt_str("markdown-highlight-theme");
+        if let Some(theme) = &markdown_highlight_theme
+            && !crate::markdown::HIGHLIGHT_THEMES.contains(&theme.as_str())
+        {
+            let themes: Vec<_> =
+                crate::markdown::HIGHLIGHT_THEMES.iter().map(|name| format!("`{name}`")).collect();
+            dcx.fatal(format!(
+                "option `--markdown-highlight-theme` argument must be one of {}, got `{theme}`",
+                themes.join(", ")
+            ));
+        }
+        let markdown_math_url = matches.opt_str("markdown-math-url");
+        let markdown_math = match (matches.opt_present("markdown-math"), markdown_math_url) {
+            (false, None) => None,
+            (false, Some(_)) => {
+                dcx.fatal("option `--markdown-math-url` requires `--markdown-math`");
+            }
+            (true, None) => Some(DEFAULT_MATHJAX_URL.to_owned()),
+            (true, Some(url)) if url.trim().is_empty() => {
+                dcx.fatal("option `--markdown-math-url` argument must not be empty");
+            }
+            (true, Some(url)) => Some(url),
+        };
+        let markdown_lang = matches.opt_str("markdown-lang").unwrap_or_else(|| "en".to_owned());
+        if markdown_lang.trim().is_empty() {
+            dcx.fatal("option `--markdown-lang` argument must not be empty");
+        }
+        let markdown_json_ld = matches.opt_present("markdown-json-ld");
+        let markdown_banner = matches.opt_str("markdown-banner");
+        let markdown_banner_html = matches.opt_present("markdown-banner-html");
+        let markdown_inline_images = matches.opt_present("markdown-inline-images");
+        let markdown_inline_image_limit = match matches.opt_str("markdown-inline-image-limit") {
+            None => 1024 * 1024,
+            Some(limit) => match limit.parse::<u64>() {
+                Ok(limit) => limit,
+                Err(_) => dcx.fatal(format!(
+                    "option `--markdown-inline-image-limit` argument must be a size in bytes, \
+                     got `{limit}`"
+                )),
+            },
+        };
+        let markdown_allowed_html_tags =
+            matches.opt_str("markdown-allowed-html-tags").map(|tags| {
+                tags.split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(|tag| tag.to_ascii_lowercase())
+                    .collect::<Vec<_>>()
+            });
+        let markdown_trim_code_whitespace = matches.opt_present("markdown-trim-code-whitespace");
+        let markdown_csp = match matches.opt_str("markdown-csp-policy") {
+            Some(policy) if policy.trim().is_empty() => {
+                dcx.fatal("option `--markdown-csp-policy` argument must not be empty")
+            }
+            Some(policy) => Some(policy),
+            None if matches.opt_present("markdown-csp") => {
+                Some(crate::markdown::default_csp(markdown_math.as_deref()))
+            }
+            None => None,
+        };
+        let markdown_filter = matches.opt_str("markdown-filter");
+        let markdown_filter_args = matches.opt_strs("markdown-filter-arg");
+        let markdown_crlf = matches.opt_present("markdown-crlf");
+        let markdown_issue_url = matches.opt_str("markdown-issue-url");
+        if markdown_issue_url.as_ref().is_some_and(|url| !url.contains("{}")) {
+            dcx.fatal("option `--markdown-issue-url` argument must contain `{}`");
+        }
+        let markdown_favicon = matches.opt_str("markdown-favicon");
+        if markdown_favicon.as_ref().is_some_and(|url| url.trim().is_empty()) {
+            dcx.fatal("option `--markdown-favicon` argument must not be empty");
+        }
+        let markdown_theme_color = matches.opt_str("markdown-theme-color");
+        if let Some(color) = &markdown_theme_color
+            && !is_color(color)
+        {
+            dcx.fatal(format!(
+                "option `--markdown-theme-color` argument must be a color like `#1a2b3c` or \
+                 `navy`, got `{color}`"
+            ));
+        }
+        let markdown_reading_time = match matches.opt_str("markdown-reading-time") {
+            None => None,
+            Some(wpm) => match wpm.parse::<u32>() {
+                Ok(wpm) if wpm > 0 => Some(wpm),
+                _ => dcx.fatal(format!(
+                    "option `--markdown-reading-time` argument must be a positive integer, \
+                     got `{wpm}`"
+                )),
+            },
+        };
         let crate_version = matches.opt_str("crate-version");
         let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
         let static_root_path = matches.opt_str("static-root-path");
@@ -739,6 +1005,7 @@ fn println_condition(condition: Condition) {
             rustc_feature::UnstableFeatures::from_environment(crate_name.as_deref());
         let options = Options {
             input,
+            more_markdown_inputs,
             bin_crate,
             proc_macro_crate,
             error_format,
@@ -771,6 +1038,7 @@ fn println_condition(condition: Condition) {
             test_builder,
             run_check,
             no_run,
+            markdown_test_lines,
             test_builder_wrappers,
             nocapture,
             crate_name,
@@ -796,8 +1064,40 @@ fn println_condition(condition: Condition) {
             index_page,
             static_root_path,
             markdown_no_toc,
+            markdown_toc_depth,
+            markdown_toc_sidebar,
+            markdown_toc_file,
+            markdown_stdout,
             markdown_css,
             markdown_playground_url,
+            markdown_upgrade_insecure,
+            markdown_autolink,
+            markdown_viewport,
+            markdown_lang,
+            markdown_index,
+            markdown_combine,
+            markdown_combine_title,
+            markdown_math,
+            markdown_back_to_top,
+            markdown_template,
+            markdown_heading_offset,
+            markdown_heading_attributes,
+            markdown_highlight_theme,
+            markdown_json_ld,
+            markdown_reading_time,
+            markdown_banner,
+            markdown_banner_html,
+            markdown_inline_images,
+            markdown_inline_image_limit,
+            markdown_allowed_html_tags,
+            markdown_trim_code_whitespace,
+            markdown_csp,
+            markdown_filter,
+            markdown_filter_args,
+            markdown_crlf,
+            markdown_issue_url,
+            markdown_favicon,
+            markdown_theme_color,
             document_private,
             document_hidden,
             generate_redirect_map,
@@ -814,12 +1114,15 @@ fn println_condition(condition: Condition) {
 
     /// Returns `true` if the file given as `self.input` is a Markdown file.
     pub(crate) fn markdown_input(&self) -> Option<&Path> {
-        self.input
-            .opt_path()
-            .filter(|p| matches!(p.extension(), Some(e) if e == "md" || e == "markdown"))
+        self.input.opt_path().filter(|p| is_markdown_path(p))
     }
 }
 
+/// Whether `path` has the extension of a Markdown file.
+fn is_markdown_path(path: &Path) -> bool {
+    matches!(path.extension(), Some(e) if e == "md" || e == "markdown")
+}
+
 /// Prints deprecation warnings for deprecated options
 fn check_deprecated_options(matches: &getopts::Matches, dcx: &rustc_errors::DiagCtxt) {
     let deprecated_flags = [];
@@ -870,3 +1173,170 @@ fn parse_extern_html_roots(
     }
     Ok(externs)
 }
+
+/// The MathJax script loaded by `--markdown-math` when no `--markdown-math-url` is given. Pages
+/// rendered with it need network access to jsDelivr's CDN to typeset their math.
+const DEFAULT_MATHJAX_URL: &str = "https://cdn.jsdelivr.net/npm/mathjax@3/es5/tex-mml-chtml.js";
+
+/// The named colors of CSS, which `--markdown-theme-color` accepts besides hexadecimal ones.
+const CSS_COLOR_NAMES: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+/// Whether `color` is a CSS color: a `#` followed by 3, 4, 6 or 8 hexadecimal digits, or one of
+/// `CSS_COLOR_NAMES`, in any case.
+fn is_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => {
+            matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => CSS_COLOR_NAMES.iter().any(|name| name.eq_ignore_ascii_case(color)),
+    }
+}
@@ -21,12 +21,13 @@
 //!     playground: &None,
 //!     heading_offset: HeadingOffset::H2,
 //!     custom_code_classes_in_docs: true,
+//!     standalone: &None,
 //! };
 //! let html = md.into_string();
 //! // ... something using html
 //! ```
 
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::{Diag, DiagMessage};
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::TyCtxt;
@@ -51,7 +52,7 @@
 use crate::html::highlight;
 use crate::html::length_limit::HtmlWithLimit;
 use crate::html::render::small_url_encode;
-use crate::html::toc::TocBuilder;
+use crate::html::toc::{Toc, TocBuilder};
 
 use pulldown_cmark::{
     html, BrokenLink, CodeBlockKind, CowStr, Event, LinkType, OffsetIter, Options, Parser, Tag,
@@ -71,6 +72,16 @@ pub(crate) fn summary_opts() -> Options {
         | Options::ENABLE_SMART_PUNCTUATION
 }
 
+/// Options for rendering Markdown in the main body of documentation. Standalone
+/// files can also accept `{#id}` attributes on headings.
+fn body_opts(heading_attributes: bool) -> Options {
+    if heading_attributes {
+        main_body_opts() | Options::ENABLE_HEADING_ATTRIBUTES
+    } else {
+        main_body_opts()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum HeadingOffset {
     H1 = 0,
@@ -99,6 +110,8 @@ pub struct Markdown<'a> {
     pub heading_offset: HeadingOffset,
     /// `true` if the `custom_code_classes_in_docs` feature is enabled.
     pub custom_code_classes_in_docs: bool,
+    /// Options only used when rendering a standalone Markdown file.
+    pub standalone: &'a Option<StandaloneOptions>,
 }
 /// A struct like `Markdown` that renders the markdown with a table of contents.
 pub(crate) struct MarkdownWithToc<'a> {
@@ -107,8 +120,13 @@ pub(crate) struct MarkdownWithToc<'a> {
     pub(crate) error_codes: ErrorCodes,
     pub(crate) edition: Edition,
     pub(crate) playground: &'a Option<Playground>,
+    /// Offset at which we render headings, like `Markdown::heading_offset`. The table of contents
+    /// is numbered from the top-level headings whatever their offset.
+    pub(crate) heading_offset: HeadingOffset,
     /// `true` if the `custom_code_classes_in_docs` feature is enabled.
     pub(crate) custom_code_classes_in_docs: bool,
+    /// Options only used when rendering a standalone Markdown file.
+    pub(crate) standalone: &'a Option<StandaloneOptions>,
 }
 /// A tuple struct like `Markdown` that renders the markdown escaping HTML tags
 /// and includes no paragraph tags.
@@ -195,12 +213,57 @@ fn slugify(c: char) -> Option<char> {
     }
 }
 
+/// The ID of a heading with the text `text`, before it is made unique by an `IdMap`.
+pub(crate) fn heading_id(text: &str) -> String {
+    text.chars().filter_map(slugify).collect()
+}
+
+/// The ID of a heading with an explicit `{#id}`. It is written into attributes as it is, so any
+/// ID with characters that generated IDs don't have is slugified.
+fn explicit_heading_id(id: &str) -> String {
+    if id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        id.to_owned()
+    } else {
+        id.chars().filter_map(slugify).collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Playground {
     pub crate_name: Option<Symbol>,
     pub url: String,
 }
 
+/// Rendering options which only apply to standalone Markdown files (`rustdoc file.md`).
+#[derive(Clone, Debug, Default)]
+pub struct StandaloneOptions {
+    /// Rewrite `http://` link and image destinations to `https://`.
+    pub upgrade_insecure_links: bool,
+    /// Turn bare `http://` and `https://` URLs in text into links.
+    pub autolink_bare_urls: bool,
+    /// Only include headings up to this level in the table of contents.
+    pub toc_depth: Option<u32>,
+    /// Show the table of contents in a collapsible sidebar next to the content.
+    pub toc_sidebar: bool,
+    /// `data:` URIs to use as the source of local images, keyed by the image destination.
+    pub inline_images: FxHashMap<String, String>,
+    /// If set, raw HTML tags which are not in this list are escaped. All tags are kept otherwise.
+    /// Allowing `math` also allows the MathML elements.
+    pub allowed_html_tags: Option<Vec<String>>,
+    /// Remove the trailing whitespace of the lines of code blocks.
+    pub trim_code_whitespace: bool,
+    /// If set, turn `#1234` references in text into links to this URL, with `{}` replaced by the
+    /// number.
+    pub issue_url: Option<String>,
+    /// If set, the ID of the top of the page, linked to from each heading.
+    pub back_to_top: Option<String>,
+    /// Parse `{#id}` attributes on headings, which replace their generated ID.
+    pub heading_attributes: bool,
+    /// The `.html` destinations of the links to the relative `.md` files which are rendered
+    /// along, keyed by the link destination. The links to other files are left alone.
+    pub markdown_links: FxHashMap<String, String>,
+}
+
 /// Adds syntax highlighting and playground Run buttons to Rust code blocks.
 struct CodeBlocks<'p, 'a, I: Iterator<Item = Event<'a>>> {
     inner: I,
@@ -459,6 +522,425 @@ fn next(&mut self) -> Option<Self::Item> {
     }
 }
 
+/// Returns the `https://` version of `url` if it is an `http://` URL pointing to a host which
+/// isn't `localhost` or an IP literal.
+pub(crate) fn upgrade_insecure_url(url: &str) -> Option<String> {
+    let scheme_len = "http://".len();
+    if !url.get(..scheme_len).is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://")) {
+        return None;
+    }
+    let rest = &url[scheme_len..];
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    // Drop the userinfo, if any.
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    if host_port.starts_with('[') {
+        // IPv6 literal.
+        return None;
+    }
+    let host = host_port.split(':').next().unwrap_or_default().trim_end_matches('.');
+    if host.is_empty()
+        || host.eq_ignore_ascii_case("localhost")
+        || host.to_ascii_lowercase().ends_with(".localhost")
+        || host.parse::<std::net::Ipv4Addr>().is_ok()
+    {
+        return None;
+    }
+    Some(format!("https://{rest}"))
+}
+
+/// Upgrades `http://` link and image destinations to `https://` (see `upgrade_insecure_url`).
+struct InsecureLinkUpgrader<'a, I: Iterator<Item = Event<'a>>> {
+    inner: I,
+    enabled: bool,
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> InsecureLinkUpgrader<'a, I> {
+    fn new(iter: I, enabled: bool) -> Self {
+        Self { inner: iter, enabled }
+    }
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> Iterator for InsecureLinkUpgrader<'a, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut event = self.inner.next();
+        if self.enabled
+            && let Some(Event::Start(Tag::Link(_, dest, _) | Tag::Image(_, dest, _))) = &mut event
+            && let Some(upgraded) = upgrade_insecure_url(dest)
+        {
+            *dest = upgraded.into();
+        }
+        event
+    }
+}
+
+/// Returns the destination of the `.html` file a link to a relative `.md` file is rendered to,
+/// keeping its fragment, as in `other.md#section` to `other.html#section`.
+pub(crate) fn markdown_link_to_html(dest: &str) -> Option<String> {
+    if !is_local_destination(dest) {
+        return None;
+    }
+    let (path, fragment) = match dest.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (dest, None),
+    };
+    let stem = path.strip_suffix(".md").filter(|stem| !stem.is_empty() && !stem.ends_with('/'))?;
+    Some(match fragment {
+        Some(fragment) => format!("{stem}.html#{fragment}"),
+        None => format!("{stem}.html"),
+    })
+}
+
+/// Points the links to relative `.md` files to the `.html` files they are rendered to (see
+/// `StandaloneOptions::markdown_links`).
+struct MarkdownLinkRewriter<'a, 'b, I: Iterator<Item = Event<'a>>> {
+    inner: I,
+    links: Option<&'b FxHashMap<String, String>>,
+}
+
+impl<'a, 'b, I: Iterator<Item = Event<'a>>> MarkdownLinkRewriter<'a, 'b, I> {
+    fn new(iter: I, links: Option<&'b FxHashMap<String, String>>) -> Self {
+        Self { inner: iter, links }
+    }
+}
+
+impl<'a, 'b, I: Iterator<Item = Event<'a>>> Iterator for MarkdownLinkRewriter<'a, 'b, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut event = self.inner.next();
+        if let Some(links) = self.links
+            && let Some(Event::Start(Tag::Link(_, dest, _))) = &mut event
+            && let Some(html) = links.get(&**dest)
+        {
+            *dest = html.clone().into();
+        }
+        event
+    }
+}
+
+/// Escapes the raw HTML tags of `event` which are not allowed (see
+/// `StandaloneOptions::allowed_html_tags`), so that they show up as text.
+fn filter_raw_html<'a>(event: Event<'a>, allowed: Option<&[String]>) -> Event<'a> {
+    match (event, allowed) {
+        (Event::Html(html), Some(allowed)) => {
+            Event::Html(escape_disallowed_html_tags(&html, allowed).into())
+        }
+        (event, _) => event,
+    }
+}
+
+/// The MathML elements which may appear inside of `<math>`. Allowing `math` allows all of them,
+/// so that hand-written MathML survives without listing every element.
+const MATHML_TAGS: &[&str] = &[
+    "annotation",
+    "annotation-xml",
+    "maction",
+    "menclose",
+    "merror",
+    "mfenced",
+    "mfrac",
+    "mi",
+    "mmultiscripts",
+    "mn",
+    "mo",
+    "mover",
+    "mpadded",
+    "mphantom",
+    "mprescripts",
+    "mroot",
+    "mrow",
+    "ms",
+    "mspace",
+    "msqrt",
+    "mstyle",
+    "msub",
+    "msubsup",
+    "msup",
+    "mtable",
+    "mtd",
+    "mtext",
+    "mtr",
+    "munder",
+    "munderover",
+    "none",
+    "semantics",
+];
+
+/// Escapes the `<` of the tags in `html` whose name is not in `allowed`. Comments and other
+/// markup without a tag name are left alone.
+fn escape_disallowed_html_tags(html: &str, allowed: &[String]) -> String {
+    let is_allowed = |name: &str| {
+        allowed.iter().any(|tag| {
+            tag.eq_ignore_ascii_case(name)
+                || (tag.eq_ignore_ascii_case("math")
+                    && MATHML_TAGS.iter().any(|mathml| mathml.eq_ignore_ascii_case(name)))
+        })
+    };
+
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let name = rest.strip_prefix('/').unwrap_or(rest);
+        let end = name.find(|c: char| !c.is_ascii_alphanumeric() && c != '-');
+        let name = &name[..end.unwrap_or(name.len())];
+        if name.is_empty() || is_allowed(name) {
+            out.push('<');
+        } else {
+            out.push_str("&lt;");
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Replaces the destination of local images with the `data:` URI embedding them (see
+/// `StandaloneOptions::inline_images`).
+struct ImageInliner<'a, 'b, I: Iterator<Item = Event<'a>>> {
+    inner: I,
+    images: Option<&'b FxHashMap<String, String>>,
+}
+
+impl<'a, 'b, I: Iterator<Item = Event<'a>>> ImageInliner<'a, 'b, I> {
+    fn new(iter: I, images: Option<&'b FxHashMap<String, String>>) -> Self {
+        Self { inner: iter, images }
+    }
+}
+
+impl<'a, 'b, I: Iterator<Item = Event<'a>>> Iterator for ImageInliner<'a, 'b, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut event = self.inner.next();
+        if let Some(images) = self.images
+            && let Some(Event::Start(Tag::Image(_, dest, _))) = &mut event
+            && let Some(uri) = images.get(&**dest)
+        {
+            *dest = uri.clone().into();
+        }
+        event
+    }
+}
+
+/// Turns bare `http://` and `https://` URLs in text into links, like GitHub does. Text inside code
+/// blocks, links and images is left alone.
+struct BareUrlLinker<'a, I: Iterator<Item = Event<'a>>> {
+    inner: Peekable<I>,
+    enabled: bool,
+    /// How many code blocks, links and images we are currently inside of.
+    skip_depth: usize,
+    stored_events: VecDeque<Event<'a>>,
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> BareUrlLinker<'a, I> {
+    fn new(iter: I, enabled: bool) -> Self {
+        Self { inner: iter.peekable(), enabled, skip_depth: 0, stored_events: VecDeque::new() }
+    }
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> Iterator for BareUrlLinker<'a, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.stored_events.pop_front() {
+            return Some(event);
+        }
+        let event = self.inner.next()?;
+        if !self.enabled {
+            return Some(event);
+        }
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::Link(..) | Tag::Image(..)) => {
+                self.skip_depth += 1;
+            }
+            Event::End(Tag::CodeBlock(_) | Tag::Link(..) | Tag::Image(..)) => {
+                self.skip_depth -= 1;
+            }
+            Event::Text(text) if self.skip_depth == 0 => {
+                // The parser may split a URL across several text events.
+                let mut text = text.into_string();
+                while let Some(Event::Text(next)) = self.inner.peek() {
+                    text.push_str(next);
+                    self.inner.next();
+                }
+                link_bare_urls(&text, &mut self.stored_events);
+                return self.stored_events.pop_front();
+            }
+            _ => {}
+        }
+        Some(event)
+    }
+}
+
+/// Returns the start of the first bare URL in `text`, if any.
+fn find_bare_url(text: &str) -> Option<usize> {
+    text.match_indices("http").map(|(start, _)| start).find(|&start| {
+        let url = &text[start..];
+        let after_scheme = url.strip_prefix("http://").or_else(|| url.strip_prefix("https://"));
+        let at_word_start =
+            !matches!(text[..start].chars().next_back(), Some(c) if c.is_alphanumeric());
+        at_word_start && after_scheme.is_some_and(|rest| rest.starts_with(char::is_alphanumeric))
+    })
+}
+
+/// Splits `text` into text and link events for the bare URLs in it.
+fn link_bare_urls<'a>(text: &str, events: &mut VecDeque<Event<'a>>) {
+    let mut rest = text;
+    while let Some(start) = find_bare_url(rest) {
+        let url = &rest[start..];
+        let url = &url[..url.find(|c: char| c.is_whitespace() || c == '<').unwrap_or(url.len())];
+        // Trailing punctuation most likely belongs to the sentence, not to the URL.
+        let mut url =
+            url.trim_end_matches(['.', ',', ':', ';', '!', '?', '\'', '"', '*', '_', '~']);
+        while url.ends_with(')') && url.matches(')').count() > url.matches('(').count() {
+            url = &url[..url.len() - 1];
+        }
+        if start > 0 {
+            events.push_back(Event::Text(rest[..start].to_owned().into()));
+        }
+        let dest = CowStr::from(url.to_owned());
+        events.push_back(Event::Start(Tag::Link(LinkType::Autolink, dest.clone(), "".into())));
+        events.push_back(Event::Text(dest.clone()));
+        events.push_back(Event::End(Tag::Link(LinkType::Autolink, dest, "".into())));
+        rest = &rest[start + url.len()..];
+    }
+    if !rest.is_empty() {
+        events.push_back(Event::Text(rest.to_owned().into()));
+    }
+}
+
+/// Turns `#1234` references in text into links to an issue tracker (see
+/// `StandaloneOptions::issue_url`). Text inside code blocks, links and images is left alone.
+struct IssueLinker<'a, 'u, I: Iterator<Item = Event<'a>>> {
+    inner: Peekable<I>,
+    url: Option<&'u str>,
+    /// How many code blocks, links and images we are currently inside of.
+    skip_depth: usize,
+    stored_events: VecDeque<Event<'a>>,
+}
+
+impl<'a, 'u, I: Iterator<Item = Event<'a>>> IssueLinker<'a, 'u, I> {
+    fn new(iter: I, url: Option<&'u str>) -> Self {
+        Self { inner: iter.peekable(), url, skip_depth: 0, stored_events: VecDeque::new() }
+    }
+}
+
+impl<'a, 'u, I: Iterator<Item = Event<'a>>> Iterator for IssueLinker<'a, 'u, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.stored_events.pop_front() {
+            return Some(event);
+        }
+        let event = self.inner.next()?;
+        let Some(url) = self.url else {
+            return Some(event);
+        };
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::Link(..) | Tag::Image(..)) => {
+                self.skip_depth += 1;
+            }
+            Event::End(Tag::CodeBlock(_) | Tag::Link(..) | Tag::Image(..)) => {
+                self.skip_depth -= 1;
+            }
+            Event::Text(text) if self.skip_depth == 0 => {
+                // The parser may split a reference across several text events.
+                let mut text = text.into_string();
+                while let Some(Event::Text(next)) = self.inner.peek() {
+                    text.push_str(next);
+                    self.inner.next();
+                }
+                link_issues(&text, url, &mut self.stored_events);
+                return self.stored_events.pop_front();
+            }
+            _ => {}
+        }
+        Some(event)
+    }
+}
+
+/// Returns the start and end of the first `#1234` reference in `text`, if any. The reference
+/// must be a word of its own, so that things like `C#1` or `#12ab` are not matched.
+fn find_issue(text: &str) -> Option<(usize, usize)> {
+    text.match_indices('#').find_map(|(start, _)| {
+        let after = &text[start + 1..];
+        let digits = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+        let end = start + 1 + digits;
+        let at_word_start =
+            !matches!(text[..start].chars().next_back(), Some(c) if c.is_alphanumeric());
+        let at_word_end = !matches!(text[end..].chars().next(), Some(c) if c.is_alphanumeric());
+        (digits > 0 && at_word_start && at_word_end).then_some((start, end))
+    })
+}
+
+/// Splits `text` into text and link events for the issue references in it, linking to `url` with
+/// `{}` replaced by the issue number.
+fn link_issues<'a>(text: &str, url: &str, events: &mut VecDeque<Event<'a>>) {
+    let mut rest = text;
+    while let Some((start, end)) = find_issue(rest) {
+        if start > 0 {
+            events.push_back(Event::Text(rest[..start].to_owned().into()));
+        }
+        let dest = CowStr::from(url.replace("{}", &rest[start + 1..end]));
+        events.push_back(Event::Start(Tag::Link(LinkType::Inline, dest.clone(), "".into())));
+        events.push_back(Event::Text(rest[start..end].to_owned().into()));
+        events.push_back(Event::End(Tag::Link(LinkType::Inline, dest, "".into())));
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() {
+        events.push_back(Event::Text(rest.to_owned().into()));
+    }
+}
+
+/// Removes the trailing whitespace of each line of code blocks, keeping their indentation.
+struct CodeWhitespaceTrimmer<'a, I: Iterator<Item = Event<'a>>> {
+    inner: I,
+    enabled: bool,
+    in_code_block: bool,
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> CodeWhitespaceTrimmer<'a, I> {
+    fn new(iter: I, enabled: bool) -> Self {
+        Self { inner: iter, enabled, in_code_block: false }
+    }
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> Iterator for CodeWhitespaceTrimmer<'a, I> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.inner.next()?;
+        if !self.enabled {
+            return Some(event);
+        }
+        Some(match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.in_code_block = true;
+                event
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                self.in_code_block = false;
+                event
+            }
+            Event::Text(text) if self.in_code_block => {
+                let trimmed: String = text
+                    .split_inclusive('\n')
+                    .map(|line| {
+                        let content = line.strip_suffix('\n').unwrap_or(line);
+                        let newline = &line[content.len()..];
+                        format!("{}{newline}", content.trim_end_matches([' ', '\t']))
+                    })
+                    .collect();
+                Event::Text(trimmed.into())
+            }
+            event => event,
+        })
+    }
+}
+
 /// Wrap HTML tables into `<div>` to prevent having the doc blocks width being too big.
 struct TableWrapper<'a, I: Iterator<Item = Event<'a>>> {
     inner: I,
@@ -504,6 +986,8 @@ struct HeadingLinks<'a, 'b, 'ids, I> {
     buf: VecDeque<SpannedEvent<'a>>,
     id_map: &'ids mut IdMap,
     heading_offset: HeadingOffset,
+    /// If set, the ID of the top of the page, linked to at the end of each heading.
+    back_to_top: Option<&'b str>,
 }
 
 impl<'a, 'b, 'ids, I> HeadingLinks<'a, 'b, 'ids, I> {
@@ -512,8 +996,16 @@ fn new(
         toc: Option<&'b mut TocBuilder>,
         ids: &'ids mut IdMap,
         heading_offset: HeadingOffset,
+        back_to_top: Option<&'b str>,
     ) -> Self {
-        HeadingLinks { inner: iter, toc, buf: VecDeque::new(), id_map: ids, heading_offset }
+        HeadingLinks {
+            inner: iter,
+            toc,
+            buf: VecDeque::new(),
+            id_map: ids,
+            heading_offset,
+            back_to_top,
+        }
     }
 }
 
@@ -528,7 +1020,7 @@ fn next(&mut self) -> Option<Self::Item> {
         }
 
         let event = self.inner.next();
-        if let Some((Event::Start(Tag::Heading(level, _, _)), _)) = event {
+        if let Some((Event::Start(Tag::Heading(level, explicit_id, _)), _)) = event {
             let mut id = String::new();
             for event in &mut self.inner {
                 match &event.0 {
@@ -540,15 +1032,25 @@ fn next(&mut self) -> Option<Self::Item> {
                     _ => self.buf.push_back(event),
                 }
             }
+            // An explicit `{#id}` (only parsed with `StandaloneOptions::heading_attributes`)
+            // replaces the generated slug.
+            let id = explicit_id.map(explicit_heading_id).filter(|id| !id.is_empty()).unwrap_or(id);
             let id = self.id_map.derive(id);
 
-            if let Some(ref mut builder) = self.toc {
+            if let Some(ref mut builder) = self.toc
+                && builder.includes(level as u32)
+            {
                 let mut html_header = String::new();
                 html::push_html(&mut html_header, self.buf.iter().map(|(ev, _)| ev.clone()));
                 let sec = builder.push(level as u32, html_header, id.clone());
                 self.buf.push_front((Event::Html(format!("{sec} ").into()), 0..0));
             }
 
+            if let Some(top) = self.back_to_top {
+                let link =
+                    format!("<a class=\"back-to-top\" href=\"#{top}\" title=\"Back to top\">↑</a>");
+                self.buf.push_back((Event::Html(link.into()), 0..0));
+            }
             let level =
                 std::cmp::min(level as u32 + (self.heading_offset as u32), MAX_HEADER_LEVEL);
             self.buf.push_back((Event::Html(format!("</h{level}>").into()), 0..0));
@@ -652,16 +1154,51 @@ fn next(&mut self) -> Option<Self::Item> {
     }
 }
 
+/// Reserve in `ids` the IDs of the footnotes of `md` and of their references, in the order in
+/// which `Footnotes` numbers them, so that they don't collide with the IDs of the headings. This
+/// is only done for standalone Markdown files, the footnotes of documentation keep their IDs.
+fn footnote_ids(
+    md: &str,
+    ids: &mut IdMap,
+    standalone: &Option<StandaloneOptions>,
+) -> Vec<(String, String)> {
+    // Avoid parsing the documentation twice in the common case.
+    if standalone.is_none() || !md.contains("[^") {
+        return Vec::new();
+    }
+    let count = Parser::new_ext(md, main_body_opts())
+        .filter_map(|event| match event {
+            Event::FootnoteReference(label) | Event::Start(Tag::FootnoteDefinition(label)) => {
+                Some(label)
+            }
+            _ => None,
+        })
+        .collect::<FxHashSet<_>>()
+        .len();
+    (1..=count).map(|n| (ids.derive(format!("fn{n}")), ids.derive(format!("fnref{n}")))).collect()
+}
+
 /// Moves all footnote definitions to the end and add back links to the
 /// references.
 struct Footnotes<'a, I> {
     inner: I,
     footnotes: FxHashMap<String, (Vec<Event<'a>>, u16)>,
+    /// The IDs of each footnote and of its reference, from `footnote_ids`. The footnotes past the
+    /// end get the `fn{id}` and `fnref{id}` IDs.
+    ids: Vec<(String, String)>,
 }
 
 impl<'a, I> Footnotes<'a, I> {
-    fn new(iter: I) -> Self {
-        Footnotes { inner: iter, footnotes: FxHashMap::default() }
+    fn new(iter: I, ids: Vec<(String, String)>) -> Self {
+        Footnotes { inner: iter, footnotes: FxHashMap::default(), ids }
+    }
+
+    /// The IDs of the footnote numbered `id` and of its reference.
+    fn ids(&self, id: u16) -> (String, String) {
+        match self.ids.get(usize::from(id) - 1) {
+            Some(ids) => ids.clone(),
+            None => (format!("fn{id}"), format!("fnref{id}")),
+        }
     }
 
     fn get_entry(&mut self, key: &str) -> &mut (Vec<Event<'a>>, u16) {
@@ -678,11 +1215,10 @@ fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next() {
                 Some((Event::FootnoteReference(ref reference), range)) => {
-                    let entry = self.get_entry(reference);
-                    let reference = format!(
-                        "<sup id=\"fnref{0}\"><a href=\"#fn{0}\">{0}</a></sup>",
-                        (*entry).1
-                    );
+                    let id = self.get_entry(reference).1;
+                    let (fn_id, ref_id) = self.ids(id);
+                    let reference =
+                        format!("<sup id=\"{ref_id}\"><a href=\"#{fn_id}\">{id}</a></sup>");
                     return Some((Event::Html(reference.into()), range));
                 }
                 Some((Event::Start(Tag::FootnoteDefinition(def)), _)) => {
@@ -703,14 +1239,15 @@ fn next(&mut self) -> Option<Self::Item> {
                         v.sort_by(|a, b| a.1.cmp(&b.1));
                         let mut ret = String::from("<div class=\"footnotes\"><hr><ol>");
                         for (mut content, id) in v {
-                            write!(ret, "<li id=\"fn{id}\">").unwrap();
+                            let (fn_id, ref_id) = self.ids(id);
+                            write!(ret, "<li id=\"{fn_id}\">").unwrap();
                             let mut is_paragraph = false;
                             if let Some(&Event::End(Tag::Paragraph)) = content.last() {
                                 content.pop();
                                 is_paragraph = true;
                             }
                             html::push_html(&mut ret, content.into_iter());
-                            write!(ret, "&nbsp;<a href=\"#fnref{id}\">↩</a>").unwrap();
+                            write!(ret, "&nbsp;<a href=\"#{ref_id}\">↩</a>").unwrap();
                             if is_paragraph {
                                 ret.push_str("</p>");
                             }
@@ -1408,6 +1945,7 @@ pub fn into_string(self) -> String {
             playground,
             heading_offset,
             custom_code_classes_in_docs,
+            standalone,
         } = self;
 
         // This is actually common enough to special-case
@@ -1421,15 +1959,38 @@ pub fn into_string(self) -> String {
                 .map(|link| (link.href.as_str().into(), link.tooltip.as_str().into()))
         };
 
-        let p = Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer));
-        let p = p.into_offset_iter();
+        let p = Parser::new_with_broken_link_callback(
+            md,
+            body_opts(standalone.as_ref().is_some_and(|opts| opts.heading_attributes)),
+            Some(&mut replacer),
+        );
+        let allowed_html_tags =
+            standalone.as_ref().and_then(|opts| opts.allowed_html_tags.as_deref());
+        let p = p
+            .into_offset_iter()
+            .map(|(event, range)| (filter_raw_html(event, allowed_html_tags), range));
 
         let mut s = String::with_capacity(md.len() * 3 / 2);
 
-        let p = HeadingLinks::new(p, None, ids, heading_offset);
-        let p = Footnotes::new(p);
+        let footnote_ids = footnote_ids(md, ids, standalone);
+        let back_to_top = standalone.as_ref().and_then(|opts| opts.back_to_top.as_deref());
+        let p = HeadingLinks::new(p, None, ids, heading_offset, back_to_top);
+        let p = Footnotes::new(p, footnote_ids);
         let p = LinkReplacer::new(p.map(|(ev, _)| ev), links);
+        let p =
+            BareUrlLinker::new(p, standalone.as_ref().is_some_and(|opts| opts.autolink_bare_urls));
+        let p = IssueLinker::new(p, standalone.as_ref().and_then(|opts| opts.issue_url.as_deref()));
+        let p = ImageInliner::new(p, standalone.as_ref().map(|opts| &opts.inline_images));
+        let p = InsecureLinkUpgrader::new(
+            p,
+            standalone.as_ref().is_some_and(|opts| opts.upgrade_insecure_links),
+        );
+        let p = MarkdownLinkRewriter::new(p, standalone.as_ref().map(|opts| &opts.markdown_links));
         let p = TableWrapper::new(p);
+        let p = CodeWhitespaceTrimmer::new(
+            p,
+            standalone.as_ref().is_some_and(|opts| opts.trim_code_whitespace),
+        );
         let p = CodeBlocks::new(p, codes, edition, playground, custom_code_classes_in_docs);
         html::push_html(&mut s, p);
 
@@ -1439,30 +2000,87 @@ pub fn into_string(self) -> String {
 
 impl MarkdownWithToc<'_> {
     pub(crate) fn into_string(self) -> String {
+        self.into_string_and_toc().0
+    }
+
+    /// Render the document with its table of contents, and also return that table of contents
+    /// on its own.
+    pub(crate) fn into_string_and_toc(self) -> (String, String) {
+        let toc_sidebar = self.standalone.as_ref().is_some_and(|opts| opts.toc_sidebar);
+        let (s, toc) = self.into_string_without_toc();
+        let toc = toc.print();
+        (with_toc(s, &toc, toc_sidebar), toc)
+    }
+
+    /// Render the document and return its table of contents separately, so that it can be merged
+    /// with the ones of other documents.
+    pub(crate) fn into_string_without_toc(self) -> (String, Toc) {
         let MarkdownWithToc {
             content: md,
             ids,
             error_codes: codes,
             edition,
             playground,
+            heading_offset,
             custom_code_classes_in_docs,
+            standalone,
         } = self;
 
-        let p = Parser::new_ext(md, main_body_opts()).into_offset_iter();
+        let allowed_html_tags =
+            standalone.as_ref().and_then(|opts| opts.allowed_html_tags.as_deref());
+        let heading_attributes = standalone.as_ref().is_some_and(|opts| opts.heading_attributes);
+        let p = Parser::new_ext(md, body_opts(heading_attributes))
+            .into_offset_iter()
+            .map(|(event, range)| (filter_raw_html(event, allowed_html_tags), range));
 
         let mut s = String::with_capacity(md.len() * 3 / 2);
 
-        let mut toc = TocBuilder::new();
+        let mut toc =
+            TocBuilder::with_max_level(standalone.as_ref().and_then(|opts| opts.toc_depth));
 
         {
-            let p = HeadingLinks::new(p, Some(&mut toc), ids, HeadingOffset::H1);
-            let p = Footnotes::new(p);
-            let p = TableWrapper::new(p.map(|(ev, _)| ev));
+            let footnote_ids = footnote_ids(md, ids, standalone);
+            let back_to_top = standalone.as_ref().and_then(|opts| opts.back_to_top.as_deref());
+            let p = HeadingLinks::new(p, Some(&mut toc), ids, heading_offset, back_to_top);
+            let p = Footnotes::new(p, footnote_ids);
+            let p = BareUrlLinker::new(
+                p.map(|(ev, _)| ev),
+                standalone.as_ref().is_some_and(|opts| opts.autolink_bare_urls),
+            );
+            let p =
+                IssueLinker::new(p, standalone.as_ref().and_then(|opts| opts.issue_url.as_deref()));
+            let p = ImageInliner::new(p, standalone.as_ref().map(|opts| &opts.inline_images));
+            let p = InsecureLinkUpgrader::new(
+                p,
+                standalone.as_ref().is_some_and(|opts| opts.upgrade_insecure_links),
+            );
+            let p =
+                MarkdownLinkRewriter::new(p, standalone.as_ref().map(|opts| &opts.markdown_links));
+            let p = TableWrapper::new(p);
+            let p = CodeWhitespaceTrimmer::new(
+                p,
+                standalone.as_ref().is_some_and(|opts| opts.trim_code_whitespace),
+            );
             let p = CodeBlocks::new(p, codes, edition, playground, custom_code_classes_in_docs);
             html::push_html(&mut s, p);
         }
 
-        format!("<nav id=\"TOC\">{toc}</nav>{s}", toc = toc.into_toc().print())
+        (s, toc.into_toc())
+    }
+}
+
+/// Put the rendered table of contents `toc` before the rendered document `s`, or in a sidebar
+/// next to it with `toc_sidebar`.
+pub(crate) fn with_toc(s: String, toc: &str, toc_sidebar: bool) -> String {
+    if toc_sidebar {
+        format!(
+            "<div class=\"toc-sidebar-layout\">\
+             <nav id=\"TOC\" class=\"toc-sidebar\">\
+             <details open><summary>Contents</summary>{toc}</details></nav>\
+             <div class=\"toc-sidebar-content\">{s}</div></div>"
+        )
+    } else {
+        format!("<nav id=\"TOC\">{toc}</nav>{s}")
     }
 }
 
@@ -1484,8 +2102,8 @@ pub(crate) fn into_string(self) -> String {
 
         let mut s = String::with_capacity(md.len() * 3 / 2);
 
-        let p = HeadingLinks::new(p, None, ids, HeadingOffset::H1);
-        let p = Footnotes::new(p);
+        let p = HeadingLinks::new(p, None, ids, HeadingOffset::H1, None);
+        let p = Footnotes::new(p, Vec::new());
         let p = TableWrapper::new(p.map(|(ev, _)| ev));
         let p = p.filter(|event| {
             !matches!(event, Event::Start(Tag::Paragraph) | Event::End(Tag::Paragraph))
@@ -1660,6 +2278,95 @@ pub(crate) fn plain_text_summary(md: &str, link_names: &[RenderedLink]) -> Strin
     s
 }
 
+/// Count the words in the text of `md`, leaving out inline code and code blocks.
+pub(crate) fn word_count(md: &str) -> usize {
+    // A single word can be split across several text events, so collect the text first.
+    let mut s = String::with_capacity(md.len());
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(md, main_body_opts()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(text) if !in_code_block => s.push_str(&text),
+            Event::HardBreak | Event::SoftBreak => s.push(' '),
+            Event::End(
+                Tag::Paragraph
+                | Tag::Heading(..)
+                | Tag::Item
+                | Tag::TableCell
+                | Tag::FootnoteDefinition(_),
+            ) => s.push(' '),
+            _ => (),
+        }
+    }
+
+    s.split_whitespace().count()
+}
+
+/// Returns the plain text of the first paragraph of `md` which has any, with its whitespace
+/// collapsed. Headings and code blocks are skipped, and inline code is kept without backticks.
+pub(crate) fn first_paragraph_text(md: &str) -> String {
+    let mut s = String::new();
+    let mut in_paragraph = false;
+
+    for event in Parser::new_ext(md, main_body_opts()) {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(Tag::Paragraph) => {
+                if !s.trim().is_empty() {
+                    break;
+                }
+                in_paragraph = false;
+            }
+            Event::Text(text) | Event::Code(text) if in_paragraph => s.push_str(&text),
+            Event::HardBreak | Event::SoftBreak if in_paragraph => s.push(' '),
+            _ => (),
+        }
+    }
+
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the destinations of the images in `md` which are relative paths, as opposed to URLs
+/// or absolute paths. These are the images `--markdown-inline-images` embeds.
+pub(crate) fn local_image_paths(md: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for event in Parser::new_ext(md, main_body_opts()) {
+        if let Event::Start(Tag::Image(_, dest, _)) = event
+            && is_local_destination(&dest)
+            && !paths.iter().any(|path| **path == *dest)
+        {
+            paths.push(dest.into_string());
+        }
+    }
+    paths
+}
+
+/// Returns the destinations of the links of `md` to relative `.md` files, with the line, counted
+/// from 1, where each link starts.
+pub(crate) fn markdown_file_links(md: &str) -> Vec<(String, usize)> {
+    let mut links = Vec::new();
+    for (event, range) in Parser::new_ext(md, main_body_opts()).into_offset_iter() {
+        if let Event::Start(Tag::Link(_, dest, _)) = event
+            && markdown_link_to_html(&dest).is_some()
+        {
+            let line = md[..range.start].matches('\n').count() + 1;
+            links.push((dest.into_string(), line));
+        }
+    }
+    links
+}
+
+/// Whether `dest` is a relative path, as opposed to a URL, an absolute path or a fragment.
+fn is_local_destination(dest: &str) -> bool {
+    let has_scheme = dest.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    !dest.is_empty() && !dest.starts_with(['/', '\\', '#']) && !has_scheme
+}
+
 #[derive(Debug)]
 pub(crate) struct MarkdownLink {
     pub kind: LinkType,
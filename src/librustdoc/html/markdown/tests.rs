@@ -1,10 +1,50 @@
 use super::{find_testable_code, plain_text_summary, short_markdown_summary};
+use super::{first_paragraph_text, local_image_paths, upgrade_insecure_url, word_count};
+use super::{markdown_file_links, markdown_link_to_html};
 use super::{
     ErrorCodes, HeadingOffset, IdMap, Ignore, LangString, LangStringToken, Markdown,
-    MarkdownItemInfo, TagIterator,
+    MarkdownItemInfo, MarkdownWithToc, StandaloneOptions, TagIterator,
 };
+use rustc_data_structures::fx::FxHashMap;
 use rustc_span::edition::{Edition, DEFAULT_EDITION};
 
+/// Render `md` like a standalone Markdown file rendered with `standalone`, or like documentation
+/// without it.
+fn render(md: &str, standalone: Option<StandaloneOptions>) -> String {
+    Markdown {
+        content: md,
+        links: &[],
+        ids: &mut IdMap::new(),
+        error_codes: ErrorCodes::Yes,
+        edition: DEFAULT_EDITION,
+        playground: &None,
+        heading_offset: HeadingOffset::H2,
+        custom_code_classes_in_docs: true,
+        standalone: &standalone,
+    }
+    .into_string()
+}
+
+/// Render `md` like a standalone Markdown file rendered with `opts`.
+fn render_standalone(md: &str, opts: StandaloneOptions) -> String {
+    render(md, Some(opts))
+}
+
+/// Render `md` with its table of contents, like a standalone Markdown file rendered with `opts`.
+fn render_standalone_with_toc(md: &str, opts: StandaloneOptions) -> String {
+    MarkdownWithToc {
+        content: md,
+        ids: &mut IdMap::new(),
+        error_codes: ErrorCodes::Yes,
+        edition: DEFAULT_EDITION,
+        playground: &None,
+        heading_offset: HeadingOffset::H2,
+        custom_code_classes_in_docs: true,
+        standalone: &Some(opts),
+    }
+    .into_string()
+}
+
 #[test]
 fn test_unique_id() {
     let input = [
@@ -306,6 +346,7 @@ fn t(input: &str, expect: &str) {
             playground: &None,
             heading_offset: HeadingOffset::H2,
             custom_code_classes_in_docs: true,
+            standalone: &None,
         }
         .into_string();
         assert_eq!(output, expect, "original: {}", input);
@@ -358,6 +399,7 @@ fn t(map: &mut IdMap, input: &str, expect: &str) {
             playground: &None,
             heading_offset: HeadingOffset::H2,
             custom_code_classes_in_docs: true,
+            standalone: &None,
         }
         .into_string();
         assert_eq!(output, expect, "original: {}", input);
@@ -395,6 +437,24 @@ fn t(map: &mut IdMap, input: &str, expect: &str) {
     );
 }
 
+#[test]
+fn test_footnote_ids() {
+    fn t(standalone: Option<StandaloneOptions>) -> String {
+        render("# fn1\n\nText[^note].\n\n[^note]: A note.\n", standalone)
+    }
+
+    // In standalone files, the footnote IDs are reserved first, so the heading gets another one.
+    let output = t(Some(StandaloneOptions::default()));
+    assert!(output.contains("<h2 id=\"fn1-1\">"), "{output}");
+    assert!(output.contains("<sup id=\"fnref1\"><a href=\"#fn1\">1</a></sup>"), "{output}");
+    assert!(output.contains("<li id=\"fn1\">"), "{output}");
+    assert!(output.contains("<a href=\"#fnref1\">↩</a>"), "{output}");
+    // The footnotes of documentation keep their IDs, and don't take any from the headings.
+    let output = t(None);
+    assert!(output.contains("<h2 id=\"fn1\">"), "{output}");
+    assert!(output.contains("<li id=\"fn1\">"), "{output}");
+}
+
 #[test]
 fn test_short_markdown_summary() {
     fn t(input: &str, expect: &str) {
@@ -464,6 +524,34 @@ fn t(input: &str, expect: &str) {
     );
 }
 
+#[test]
+fn test_word_count() {
+    fn t(input: &str, expect: usize) {
+        assert_eq!(word_count(input), expect, "original: {}", input);
+    }
+
+    t("", 0);
+    t("hello world", 2);
+    t("it's *very* **bold**", 3);
+    t("# header\n\nfirst paragraph\nsoft break\n\nsecond", 6);
+    t("- one\n- two\n- three", 3);
+    t("code `let x = i32;` here", 2);
+    t("before\n\n```\nfn main() { let x = 1; }\n```\n\nafter", 2);
+    t("[a link](https://www.rust-lang.org)", 2);
+}
+
+#[test]
+fn test_first_paragraph_text() {
+    fn t(input: &str, expect: &str) {
+        assert_eq!(first_paragraph_text(input), expect, "original: {}", input);
+    }
+
+    t("", "");
+    t("# header\n\n```\ncode\n```\n", "");
+    t("# header\n\nfirst *paragraph*\nsoft  break\n\nsecond", "first paragraph soft break");
+    t("![logo](logo.png)\n\ncall `foo()` [here](https://www.rust-lang.org)", "call foo() here");
+}
+
 #[test]
 fn test_markdown_html_escape() {
     fn t(input: &str, expect: &str) {
@@ -507,6 +595,7 @@ fn t(input: &str, expect: &str) {
             playground: &None,
             heading_offset: HeadingOffset::H2,
             custom_code_classes_in_docs: true,
+            standalone: &None,
         }
         .into_string();
         assert_eq!(output, expect, "original: {}", input);
@@ -539,3 +628,331 @@ fn t(input: &str, expect: &str) {
 </code></pre></div>",
     );
 }
+
+#[test]
+fn test_upgrade_insecure_links() {
+    fn t(input: &str, expect: &str) {
+        let opts = StandaloneOptions { upgrade_insecure_links: true, ..Default::default() };
+        let output = render_standalone(input, opts);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("[a](http://example.com/a?b#c)", "<p><a href=\"https://example.com/a?b#c\">a</a></p>\n");
+    t(
+        "![a](http://example.com/a.png)",
+        "<p><img src=\"https://example.com/a.png\" alt=\"a\" /></p>\n",
+    );
+    t("[a](http://localhost:8000/a)", "<p><a href=\"http://localhost:8000/a\">a</a></p>\n");
+    t("[a](http://127.0.0.1/a)", "<p><a href=\"http://127.0.0.1/a\">a</a></p>\n");
+    t("[a](#anchor) [b](b.html)", "<p><a href=\"#anchor\">a</a> <a href=\"b.html\">b</a></p>\n");
+}
+
+#[test]
+fn test_toc_depth() {
+    fn t(toc_depth: Option<u32>, expect_toc: &str) {
+        let input = "# A\n## B\n### C\n#### D";
+        let opts = StandaloneOptions { toc_depth, ..Default::default() };
+        let output = render_standalone_with_toc(input, opts);
+        let (toc, body) = output.split_once("</nav>").unwrap();
+        assert_eq!(toc, expect_toc, "toc_depth: {toc_depth:?}");
+        // Headings left out of the table of contents are still rendered, without a number.
+        assert!(body.contains("<h5 id=\"d\"><a class=\"doc-anchor\" href=\"#d\">§</a>"));
+    }
+
+    t(
+        Some(2),
+        "<nav id=\"TOC\"><ul>\n<li><a href=\"#a\">1 A</a><ul>\n<li><a href=\"#b\">1.1 B</a>\
+         </li></ul></li></ul>",
+    );
+    t(
+        None,
+        "<nav id=\"TOC\"><ul>\n<li><a href=\"#a\">1 A</a><ul>\n<li><a href=\"#b\">1.1 B</a><ul>\n\
+         <li><a href=\"#c\">1.1.1 C</a><ul>\n<li><a href=\"#d\">1.1.1.1 D</a></li></ul></li></ul>\
+         </li></ul></li></ul>",
+    );
+}
+
+#[test]
+fn test_heading_ids_with_and_without_toc() {
+    fn heading_ids(html: &str) -> Vec<&str> {
+        html.split("<h")
+            .filter_map(|tag| tag.get(1..)?.strip_prefix(" id=\"")?.split('"').next())
+            .collect()
+    }
+
+    let input = "# Usage\n## Usage\n### `Code` *em*\n## Custom {#custom}\n# fn1\n\nText[^a].\n\n\
+                 [^a]: Note.";
+    let opts = || StandaloneOptions { heading_attributes: true, ..Default::default() };
+    let with_toc = render_standalone_with_toc(input, opts());
+    let without_toc = render_standalone(input, opts());
+    let expected = ["usage", "usage-1", "code-em", "custom", "fn1-1"];
+    assert_eq!(heading_ids(&with_toc), expected, "{with_toc}");
+    assert_eq!(heading_ids(&without_toc), expected, "{without_toc}");
+}
+
+#[test]
+fn test_toc_sidebar() {
+    fn t(toc_sidebar: bool) -> String {
+        let opts = StandaloneOptions { toc_sidebar, ..Default::default() };
+        render_standalone_with_toc("# A\ntext", opts)
+    }
+
+    let toc = "<ul>\n<li><a href=\"#a\">1 A</a></li></ul>";
+    let sidebar = t(true);
+    assert!(
+        sidebar.starts_with(&format!(
+            "<div class=\"toc-sidebar-layout\"><nav id=\"TOC\" class=\"toc-sidebar\">\
+             <details open><summary>Contents</summary>{toc}</details></nav>\
+             <div class=\"toc-sidebar-content\"><h2 id=\"a\">"
+        )),
+        "{sidebar}"
+    );
+    assert!(sidebar.ends_with("<p>text</p>\n</div></div>"), "{sidebar}");
+    let inline = t(false);
+    assert!(inline.starts_with(&format!("<nav id=\"TOC\">{toc}</nav><h2 id=\"a\">")), "{inline}");
+    assert!(inline.ends_with("<p>text</p>\n"), "{inline}");
+}
+
+#[test]
+fn test_autolink_bare_urls() {
+    fn t(input: &str, expect: &str) {
+        let opts = StandaloneOptions { autolink_bare_urls: true, ..Default::default() };
+        let output = render_standalone(input, opts);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t(
+        "Visit http://example.com today",
+        "<p>Visit <a href=\"http://example.com\">http://example.com</a> today</p>\n",
+    );
+    t(
+        "See https://example.com/a_b?c=d.",
+        "<p>See <a href=\"https://example.com/a_b?c=d\">https://example.com/a_b?c=d</a>.</p>\n",
+    );
+    t(
+        "(https://example.com/a_(b))",
+        "<p>(<a href=\"https://example.com/a_(b)\">https://example.com/a_(b)</a>)</p>\n",
+    );
+    t(
+        "Run `curl http://example.com` today",
+        "<p>Run <code>curl http://example.com</code> today</p>\n",
+    );
+    t(
+        "```text\nhttp://example.com\n```",
+        "<div class=\"example-wrap\"><pre class=\"language-text\"><code>http://example.com\n</code></pre></div>",
+    );
+    t(
+        "[a link](https://example.com/a) to http://example.com/b",
+        "<p><a href=\"https://example.com/a\">a link</a> to <a href=\"http://example.com/b\">http://example.com/b</a></p>\n",
+    );
+    t("nohttp://example.com", "<p>nohttp://example.com</p>\n");
+}
+
+#[test]
+fn test_upgrade_insecure_url() {
+    assert_eq!(upgrade_insecure_url("http://example.com"), Some("https://example.com".to_owned()));
+    assert_eq!(
+        upgrade_insecure_url("HTTP://user@example.com:8080/a"),
+        Some("https://user@example.com:8080/a".to_owned()),
+    );
+    assert_eq!(upgrade_insecure_url("https://example.com"), None);
+    assert_eq!(upgrade_insecure_url("http://LocalHost/"), None);
+    assert_eq!(upgrade_insecure_url("http://foo.localhost/"), None);
+    assert_eq!(upgrade_insecure_url("http://10.0.0.1:80/"), None);
+    assert_eq!(upgrade_insecure_url("http://[::1]/"), None);
+    assert_eq!(upgrade_insecure_url("mailto:a@example.com"), None);
+}
+
+#[test]
+fn test_local_image_paths() {
+    assert_eq!(
+        local_image_paths(
+            "![a](logo.png) ![b](img/diagram.svg) ![c](logo.png)\n\n\
+             ![d](https://example.com/d.png) ![e](/abs.png) ![f](data:image/png;base64,AA==)",
+        ),
+        ["logo.png", "img/diagram.svg"],
+    );
+    assert!(local_image_paths("[not an image](logo.png)").is_empty());
+}
+
+#[test]
+fn test_markdown_link_to_html() {
+    fn t(dest: &str, expect: Option<&str>) {
+        assert_eq!(markdown_link_to_html(dest).as_deref(), expect, "original: {}", dest);
+    }
+
+    t("other.md", Some("other.html"));
+    t("guide/other.md#section", Some("guide/other.html#section"));
+    t("../other.md#", Some("../other.html#"));
+    t("other.md.txt", None);
+    t("guide/.md", None);
+    t("#section", None);
+    t("/abs/other.md", None);
+    t("https://example.com/other.md", None);
+}
+
+#[test]
+fn test_markdown_file_links() {
+    assert_eq!(
+        markdown_file_links("[a](a.md)\n\n[b](https://example.com/b.md)\n[c](c.md#x) [d](d.txt)"),
+        [("a.md".to_owned(), 1), ("c.md#x".to_owned(), 4)],
+    );
+}
+
+#[test]
+fn test_inline_images() {
+    let mut inline_images = FxHashMap::default();
+    inline_images.insert("logo.png".to_owned(), "data:image/png;base64,AA==".to_owned());
+    let output = render_standalone(
+        "![logo](logo.png) ![remote](https://example.com/remote.png)",
+        StandaloneOptions { inline_images, ..Default::default() },
+    );
+    assert_eq!(
+        output,
+        "<p><img src=\"data:image/png;base64,AA==\" alt=\"logo\" /> \
+         <img src=\"https://example.com/remote.png\" alt=\"remote\" /></p>\n",
+    );
+}
+
+#[test]
+fn test_allowed_html_tags() {
+    fn t(input: &str, expect: &str) {
+        let opts = StandaloneOptions {
+            allowed_html_tags: Some(["details", "summary", "kbd"].map(str::to_owned).to_vec()),
+            ..Default::default()
+        };
+        let output = render_standalone(input, opts);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("Press <kbd>Ctrl</kbd>", "<p>Press <kbd>Ctrl</kbd></p>\n");
+    t(
+        "<details>\n<summary>More</summary>\n</details>",
+        "<details>\n<summary>More</summary>\n</details>",
+    );
+    t("<script>alert(1)</script>", "&lt;script>alert(1)&lt;/script>");
+    t("Some <b>bold</b> text", "<p>Some &lt;b>bold&lt;/b> text</p>\n");
+    t("<!-- a comment -->", "<!-- a comment -->");
+}
+
+#[test]
+fn test_mathml_passthrough() {
+    fn t(allowed_html_tags: Option<Vec<String>>, input: &str, expect: &str) {
+        let opts = StandaloneOptions { allowed_html_tags, ..Default::default() };
+        let output = render_standalone(input, opts);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    let math = "<math><mfrac><msup><mi>x</mi><mn>2</mn></msup><mi>y</mi></mfrac></math>";
+    let expect = format!("<p>{math}</p>\n");
+    t(None, math, &expect);
+    t(Some(vec!["math".to_owned()]), math, &expect);
+    t(
+        Some(vec!["math".to_owned()]),
+        "<math><mi>x</mi></math><script>alert(1)</script>",
+        "<p><math><mi>x</mi></math>&lt;script>alert(1)&lt;/script></p>\n",
+    );
+    t(
+        Some(vec!["kbd".to_owned()]),
+        "<math><mi>x</mi></math>",
+        "<p>&lt;math>&lt;mi>x&lt;/mi>&lt;/math></p>\n",
+    );
+}
+
+#[test]
+fn test_trim_code_whitespace() {
+    fn t(trim_code_whitespace: bool, input: &str, expect: &str) {
+        let opts = StandaloneOptions { trim_code_whitespace, ..Default::default() };
+        let output = render_standalone(input, opts);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    let input = "Hard  \nbreak\n\n```text\nfoo   \n    bar\t\n```";
+    t(
+        true,
+        input,
+        "<p>Hard<br />\nbreak</p>\n<div class=\"example-wrap\"><pre class=\"language-text\">\
+         <code>foo\n    bar\n</code></pre></div>",
+    );
+    t(
+        false,
+        input,
+        "<p>Hard<br />\nbreak</p>\n<div class=\"example-wrap\"><pre class=\"language-text\">\
+         <code>foo   \n    bar\t\n</code></pre></div>",
+    );
+}
+
+#[test]
+fn test_explicit_heading_ids() {
+    fn heading_attributes() -> StandaloneOptions {
+        StandaloneOptions { heading_attributes: true, ..Default::default() }
+    }
+    fn t(standalone: Option<StandaloneOptions>, input: &str, expect: &str) {
+        let output = render(input, standalone);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t(
+        Some(heading_attributes()),
+        "# Title {#my-anchor}",
+        "<h2 id=\"my-anchor\"><a class=\"doc-anchor\" href=\"#my-anchor\">§</a>Title</h2>",
+    );
+    // Explicit ids are deduplicated against the generated ones.
+    t(
+        Some(heading_attributes()),
+        "# Title\n# Other {#title}",
+        "<h2 id=\"title\"><a class=\"doc-anchor\" href=\"#title\">§</a>Title</h2>\
+         <h2 id=\"title-1\"><a class=\"doc-anchor\" href=\"#title-1\">§</a>Other</h2>",
+    );
+    // Explicit ids which could break out of the attribute are slugified.
+    t(
+        Some(heading_attributes()),
+        "# Title {#x\"><script>alert(1)</script>}",
+        "<h2 id=\"xscriptalert1script\">\
+         <a class=\"doc-anchor\" href=\"#xscriptalert1script\">§</a>Title</h2>",
+    );
+    t(
+        Some(heading_attributes()),
+        "# Title {#\"<>}",
+        "<h2 id=\"title\"><a class=\"doc-anchor\" href=\"#title\">§</a>Title</h2>",
+    );
+    // The attribute syntax is only parsed when standalone files opt into it.
+    t(
+        Some(StandaloneOptions::default()),
+        "# Title {#my-anchor}",
+        "<h2 id=\"title-my-anchor\"><a class=\"doc-anchor\" href=\"#title-my-anchor\">§</a>\
+         Title {#my-anchor}</h2>",
+    );
+    t(
+        None,
+        "# Title {#my-anchor}",
+        "<h2 id=\"title-my-anchor\"><a class=\"doc-anchor\" href=\"#title-my-anchor\">§</a>\
+         Title {#my-anchor}</h2>",
+    );
+}
+
+#[test]
+fn test_issue_links() {
+    fn t(input: &str, expect: &str) {
+        let opts = StandaloneOptions {
+            issue_url: Some("https://example.com/issues/{}".to_owned()),
+            ..Default::default()
+        };
+        let output = render_standalone(input, opts);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t(
+        "Fixed in #1234.",
+        "<p>Fixed in <a href=\"https://example.com/issues/1234\">#1234</a>.</p>\n",
+    );
+    t("Not in code: `#1234`", "<p>Not in code: <code>#1234</code></p>\n");
+    t(
+        "[a link](#1234) and C#1, #12ab, #",
+        "<p><a href=\"#1234\">a link</a> and C#1, #12ab, #</p>\n",
+    );
+    t(
+        "```text\n#1234\n```",
+        "<div class=\"example-wrap\"><pre class=\"language-text\"><code>#1234\n</code></pre></div>",
+    );
+}
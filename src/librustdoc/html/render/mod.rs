@@ -507,6 +507,7 @@ fn scrape_examples_help(shared: &SharedContext<'_>) -> String {
             playground: &shared.playground,
             heading_offset: HeadingOffset::H1,
             custom_code_classes_in_docs: false,
+            standalone: &None,
         }
         .into_string()
     )
@@ -553,6 +554,7 @@ fn render_markdown<'a, 'cx: 'a>(
                 playground: &cx.shared.playground,
                 heading_offset,
                 custom_code_classes_in_docs,
+                standalone: &None,
             }
             .into_string()
         )
@@ -1891,6 +1893,7 @@ fn render_default_items(
                     playground: &cx.shared.playground,
                     heading_offset: HeadingOffset::H4,
                     custom_code_classes_in_docs,
+                    standalone: &None,
                 }
                 .into_string()
             );
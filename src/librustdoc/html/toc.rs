@@ -23,6 +23,30 @@ impl Toc {
     fn count_entries_with_level(&self, level: u32) -> usize {
         self.entries.iter().filter(|e| e.level == level).count()
     }
+
+    /// A table of contents with an entry for each of `sections`, given by their name, ID and own
+    /// table of contents. The entries of a section are numbered below it, like `2.1` for the first
+    /// heading of the second section.
+    pub(crate) fn from_sections(sections: Vec<(String, String, Toc)>) -> Toc {
+        let entries = sections
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, id, mut children))| {
+                let sec_number = (index + 1).to_string();
+                children.prefix_sec_numbers(&sec_number);
+                // The sections are above all the headings, which are at least level 1.
+                TocEntry { level: 0, sec_number, name, id, children }
+            })
+            .collect();
+        Toc { entries }
+    }
+
+    fn prefix_sec_numbers(&mut self, prefix: &str) {
+        for entry in &mut self.entries {
+            entry.sec_number = format!("{prefix}.{}", entry.sec_number);
+            entry.children.prefix_sec_numbers(prefix);
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -47,11 +71,22 @@ pub(crate) struct TocBuilder {
     ///
     /// We also have `chain[0].level <= top_level.entries[last]`.
     chain: Vec<TocEntry>,
+    /// Headings with a higher level than this are left out of the table of contents.
+    max_level: Option<u32>,
 }
 
 impl TocBuilder {
     pub(crate) fn new() -> TocBuilder {
-        TocBuilder { top_level: Toc { entries: Vec::new() }, chain: Vec::new() }
+        TocBuilder::with_max_level(None)
+    }
+
+    pub(crate) fn with_max_level(max_level: Option<u32>) -> TocBuilder {
+        TocBuilder { top_level: Toc { entries: Vec::new() }, chain: Vec::new(), max_level }
+    }
+
+    /// Whether a level `level` heading belongs in the table of contents.
+    pub(crate) fn includes(&self, level: u32) -> bool {
+        !self.max_level.is_some_and(|max_level| level > max_level)
     }
 
     /// Converts into a true `Toc` struct.
@@ -77,3 +77,20 @@ macro_rules! toc {
     );
     assert_eq!(expected, builder.into_toc());
 }
+
+#[test]
+fn from_sections() {
+    let mut first = TocBuilder::new();
+    first.push(1, "A".to_string(), "a".to_string());
+    first.push(2, "B".to_string(), "b".to_string());
+    let toc = Toc::from_sections(vec![
+        ("One".to_string(), "one".to_string(), first.into_toc()),
+        ("Two".to_string(), "two".to_string(), TocBuilder::new().into_toc()),
+    ]);
+    assert_eq!(
+        toc.print(),
+        "<ul>\n<li><a href=\"#one\">1 One</a><ul>\
+         \n<li><a href=\"#a\">1.1 A</a><ul>\n<li><a href=\"#b\">1.1.1 B</a><ul></ul></li></ul></li>\
+         </ul></li>\n<li><a href=\"#two\">2 Two</a><ul></ul></li></ul>"
+    );
+}
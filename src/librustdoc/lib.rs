@@ -341,6 +341,258 @@ fn opts() -> Vec<RustcOptGroup> {
         stable("markdown-no-toc", |o| {
             o.optflagmulti("", "markdown-no-toc", "don't include table of contents")
         }),
+        unstable("markdown-toc-depth", |o| {
+            o.optopt(
+                "",
+                "markdown-toc-depth",
+                "only include headings up to level N in the table of contents",
+                "N",
+            )
+        }),
+        unstable("markdown-upgrade-insecure", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-upgrade-insecure",
+                "rewrite `http://` links and images to `https://` in a rendered Markdown file",
+            )
+        }),
+        unstable("markdown-autolink", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-autolink",
+                "turn bare `http://` and `https://` URLs into links in a rendered Markdown file",
+            )
+        }),
+        unstable("markdown-viewport", |o| {
+            o.optopt(
+                "",
+                "markdown-viewport",
+                "content of the viewport <meta> tag in a rendered Markdown file",
+                "CONTENT",
+            )
+        }),
+        unstable("markdown-json-ld", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-json-ld",
+                "emit a JSON-LD Article block built from the metadata of a rendered Markdown file",
+            )
+        }),
+        unstable("markdown-reading-time", |o| {
+            o.optopt(
+                "",
+                "markdown-reading-time",
+                "show a reading time estimate at WPM words per minute in a rendered Markdown file",
+                "WPM",
+            )
+        }),
+        unstable("markdown-banner", |o| {
+            o.optopt(
+                "",
+                "markdown-banner",
+                "show a banner at the top of a rendered Markdown file, instead of the one from its \
+                 `banner` metadata",
+                "TEXT",
+            )
+        }),
+        unstable("markdown-banner-html", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-banner-html",
+                "treat the banner of `--markdown-banner` as HTML instead of text",
+            )
+        }),
+        unstable("markdown-inline-images", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-inline-images",
+                "embed the images of a rendered Markdown file given by relative paths into the \
+                 page",
+            )
+        }),
+        unstable("markdown-inline-image-limit", |o| {
+            o.optopt(
+                "",
+                "markdown-inline-image-limit",
+                "the largest image in bytes that --markdown-inline-images embeds (default: 1 MiB)",
+                "BYTES",
+            )
+        }),
+        unstable("markdown-allowed-html-tags", |o| {
+            o.optopt(
+                "",
+                "markdown-allowed-html-tags",
+                "comma-separated list of raw HTML tags to keep in a rendered Markdown file, \
+                 escaping the others (default: keep all tags)",
+                "TAGS",
+            )
+        }),
+        unstable("markdown-trim-code-whitespace", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-trim-code-whitespace",
+                "remove trailing whitespace from the lines of code blocks in a rendered Markdown \
+                 file",
+            )
+        }),
+        unstable("markdown-csp", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-csp",
+                "add a Content Security Policy forbidding inline scripts to a rendered Markdown \
+                 file",
+            )
+        }),
+        unstable("markdown-csp-policy", |o| {
+            o.optopt(
+                "",
+                "markdown-csp-policy",
+                "add this Content Security Policy to a rendered Markdown file",
+                "POLICY",
+            )
+        }),
+        unstable("markdown-filter", |o| {
+            o.optopt(
+                "",
+                "markdown-filter",
+                "pipe a rendered Markdown file through this program, which reads the HTML on its \
+                 stdin and writes the HTML to keep on its stdout",
+                "PROGRAM",
+            )
+        }),
+        unstable("markdown-filter-arg", |o| {
+            o.optmulti(
+                "",
+                "markdown-filter-arg",
+                "one (of possibly many) arguments to pass to the --markdown-filter program",
+                "ARG",
+            )
+        }),
+        unstable("markdown-toc-sidebar", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-toc-sidebar",
+                "show the table of contents of a rendered Markdown file in a sidebar",
+            )
+        }),
+        unstable("markdown-crlf", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-crlf",
+                "write a rendered Markdown file with CRLF line endings",
+            )
+        }),
+        unstable("markdown-issue-url", |o| {
+            o.optopt(
+                "",
+                "markdown-issue-url",
+                "link `#1234` references in a rendered Markdown file to this URL, with `{}` \
+                 replaced by the number",
+                "URL",
+            )
+        }),
+        unstable("markdown-favicon", |o| {
+            o.optopt("", "markdown-favicon", "use this icon for a rendered Markdown file", "URL")
+        }),
+        unstable("markdown-theme-color", |o| {
+            o.optopt(
+                "",
+                "markdown-theme-color",
+                "tint the browser interface with this color when showing a rendered Markdown file",
+                "COLOR",
+            )
+        }),
+        unstable("markdown-toc-file", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-toc-file",
+                "also write the table of contents of a rendered Markdown file to a `.toc.html` \
+                 file",
+            )
+        }),
+        unstable("markdown-lang", |o| {
+            o.optopt("", "markdown-lang", "language of a rendered Markdown file", "LANG")
+        }),
+        unstable("markdown-index", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-index",
+                "also write an `index.html` page linking to the rendered Markdown files",
+            )
+        }),
+        unstable("markdown-math", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-math",
+                "typeset the `$...$` and `$$...$$` math of a rendered Markdown file with MathJax",
+            )
+        }),
+        unstable("markdown-math-url", |o| {
+            o.optopt(
+                "",
+                "markdown-math-url",
+                "load MathJax from this URL for `--markdown-math`, instead of jsDelivr's CDN",
+                "URL",
+            )
+        }),
+        unstable("markdown-back-to-top", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-back-to-top",
+                "link each heading of a rendered Markdown file to the top of the page",
+            )
+        }),
+        unstable("markdown-template", |o| {
+            o.optopt(
+                "",
+                "markdown-template",
+                "HTML file to render Markdown files into, with `{{title}}` and `{{content}}` \
+                 placeholders",
+                "PATH",
+            )
+        }),
+        unstable("markdown-heading-offset", |o| {
+            o.optopt(
+                "",
+                "markdown-heading-offset",
+                "render the `#` headings of a Markdown file as `<hN+1>`, from 0 to 5",
+                "N",
+            )
+        }),
+        unstable("markdown-highlight-theme", |o| {
+            o.optopt(
+                "",
+                "markdown-highlight-theme",
+                "color the Rust code blocks of a rendered Markdown file like a bundled theme",
+                "light|dark|ayu",
+            )
+        }),
+        unstable("markdown-test-line", |o| {
+            o.optmulti(
+                "",
+                "markdown-test-line",
+                "only run the Markdown doctest at this line",
+                "[FILE:]LINE",
+            )
+        }),
+        unstable("markdown-heading-attributes", |o| {
+            o.optflagmulti(
+                "",
+                "markdown-heading-attributes",
+                "accept `{#id}` attributes on Markdown headings",
+            )
+        }),
+        unstable("markdown-combine", |o| {
+            o.optflagmulti("", "markdown-combine", "render the Markdown files into a single page")
+        }),
+        unstable("markdown-combine-title", |o| {
+            o.optopt(
+                "",
+                "markdown-combine-title",
+                "title of the page of `--markdown-combine`",
+                "TITLE",
+            )
+        }),
         stable("e", |o| {
             o.optopt(
                 "e",
@@ -733,7 +985,8 @@ fn main_args(
         (true, Some(_)) => return wrap_return(&diag, markdown::test(options)),
         (true, None) => return doctest::run(&diag, options),
         (false, Some(input)) => {
-            let input = input.to_owned();
+            let mut inputs = vec![input.to_owned()];
+            inputs.extend(options.more_markdown_inputs.iter().cloned());
             let edition = options.edition;
             let config = core::create_config(options, &render_options, using_internal_features);
 
@@ -742,8 +995,26 @@ fn main_args(
             // `run_compiler`.
             return wrap_return(
                 &diag,
-                interface::run_compiler(config, |_compiler| {
-                    markdown::render(&input, render_options, edition)
+                interface::run_compiler(config, |compiler| {
+                    if render_options.markdown_combine {
+                        markdown::render_combined(
+                            &inputs,
+                            render_options,
+                            edition,
+                            compiler.sess.dcx(),
+                        )
+                    } else if let [input] = &*inputs
+                        && !render_options.markdown_index
+                    {
+                        markdown::render(input, render_options, edition, compiler.sess.dcx())
+                    } else {
+                        markdown::render_many(
+                            &inputs,
+                            render_options,
+                            edition,
+                            compiler.sess.dcx(),
+                        )
+                    }
                 }),
             );
         }
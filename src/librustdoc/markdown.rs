@@ -1,10 +1,16 @@
 use std::fmt::Write as _;
 use std::fs::{create_dir_all, read_to_string, File};
-use std::io::prelude::*;
-use std::path::Path;
+use std::io::{self, prelude::*};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
 
+use base64::prelude::*;
 use tempfile::tempdir;
 
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_errors::DiagCtxt;
+use rustc_span::edit_distance::edit_distance;
 use rustc_span::edition::Edition;
 use rustc_span::DUMMY_SP;
 
@@ -14,7 +20,78 @@
 use crate::html::markdown;
 use crate::html::markdown::{
     find_testable_code, ErrorCodes, HeadingOffset, IdMap, Markdown, MarkdownWithToc,
+    StandaloneOptions,
 };
+use crate::html::static_files;
+use crate::html::toc::Toc;
+
+/// The base of the Content Security Policy used by `--markdown-csp`. It forbids inline scripts,
+/// and leaves the other resources of the page, like external stylesheets and images, alone.
+const DEFAULT_CSP: &str = "script-src 'self'; object-src 'none'; base-uri 'self'";
+
+/// The Content Security Policy used by `--markdown-csp`: `DEFAULT_CSP`, which also allows the
+/// scripts of the origin MathJax is loaded from with `--markdown-math`.
+pub(crate) fn default_csp(math_url: Option<&str>) -> String {
+    let origin = math_url.and_then(|url| {
+        let (scheme, rest) = url.split_once("://")?;
+        let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        Some(format!("{scheme}://{host}"))
+    });
+    match origin {
+        Some(origin) => {
+            DEFAULT_CSP.replacen("script-src 'self'", &format!("script-src 'self' {origin}"), 1)
+        }
+        None => DEFAULT_CSP.to_owned(),
+    }
+}
+
+/// The layout used by `--markdown-toc-sidebar`. The sidebar sticks to the side of the content,
+/// and goes back above it on narrow screens. It comes before the `--markdown-css` files, so that
+/// they can override it.
+const TOC_SIDEBAR_CSS: &str = "\
+.toc-sidebar-layout { display: flex; align-items: flex-start; gap: 2em; }
+.toc-sidebar { position: sticky; top: 0; flex: 0 0 15em; max-height: 100vh; overflow-y: auto; }
+.toc-sidebar-content { flex: 1; min-width: 0; }
+@media (max-width: 700px) {
+    .toc-sidebar-layout { display: block; }
+    .toc-sidebar { position: static; max-height: none; }
+}";
+
+/// The MathJax configuration of `--markdown-math`. MathJax doesn't recognize `$...$` as inline
+/// math by default. It is written to `MATHJAX_CONFIG_FILE` rather than inlined in the pages, so
+/// that the `DEFAULT_CSP` of `--markdown-csp` doesn't block it.
+const MATHJAX_CONFIG: &str = r"window.MathJax = {
+    tex: {
+        inlineMath: [['$', '$'], ['\\(', '\\)']],
+        displayMath: [['$$', '$$'], ['\\[', '\\]']],
+    },
+};";
+
+/// The file, next to the rendered pages, which `MATHJAX_CONFIG` is written to.
+const MATHJAX_CONFIG_FILE: &str = "mathjax-config.js";
+
+/// The length, in characters, to which the description taken from the first paragraph of a file
+/// is truncated. Search engines show about that much of it.
+const MAX_DESCRIPTION_LEN: usize = 160;
+
+/// The themes of `--markdown-highlight-theme`, which are the ones bundled with rustdoc.
+pub(crate) const HIGHLIGHT_THEMES: &[&str] = &["light", "dark", "ayu"];
+
+/// The keys of the `key: value` metadata lines which are used when rendering a file. The other
+/// `%` lines are only reported when their key is close to one of these, as it is then most likely
+/// a typo.
+const KNOWN_METADATA_KEYS: &[&str] = &[
+    "title",
+    "author",
+    "date",
+    "description",
+    "css",
+    "lang",
+    "favicon",
+    "canonical",
+    "banner",
+    "status",
+];
 
 /// Separate any lines at the start of the file that begin with `# ` or `%`.
 fn extract_leading_metadata(s: &str) -> (Vec<&str>, &str) {
@@ -35,83 +112,874 @@ fn extract_leading_metadata(s: &str) -> (Vec<&str>, &str) {
     (metadata, "")
 }
 
+/// Separate a `---`-delimited YAML frontmatter block at the start of the file, returning its
+/// `key: value` pairs. Only single-line scalar values are understood; other lines are ignored.
+fn extract_frontmatter(s: &str) -> Option<(FxIndexMap<&str, &str>, &str)> {
+    let mut lines = s.split_inclusive('\n');
+    let first = lines.next()?;
+    if first.trim_end() != "---" {
+        return None;
+    }
+    let mut count = first.len();
+    let mut fields = FxIndexMap::default();
+    for line in lines {
+        count += line.len();
+        let line = line.trim_end();
+        if line == "---" || line == "..." {
+            return Some((fields, &s[count..]));
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            // Strip the quotes of a quoted scalar.
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            if !key.trim().is_empty() && !value.is_empty() {
+                fields.insert(key.trim(), value);
+            }
+        }
+    }
+
+    // The block is never closed, so this is not frontmatter.
+    None
+}
+
+/// If the first line of the file looks like a title that is missing the space after the `#`,
+/// return the line that was probably meant.
+fn near_miss_title(s: &str) -> Option<String> {
+    let line = s.lines().next()?;
+    let title = line.strip_prefix('#')?.trim_start_matches('#').trim();
+    (!title.is_empty()).then(|| format!("# {title}"))
+}
+
+/// Split a metadata line into its key and value, if the key is a word like `author`. Other `%`
+/// lines with a colon, like `% 2024-01-01 10:00` or `% https://example.com`, are left alone.
+fn metadata_entry(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let (key, value) = (key.trim(), value.trim());
+    let is_word = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    (is_word && !value.is_empty() && !value.starts_with("//")).then_some((key, value))
+}
+
+/// Whether `key` is not a known metadata key but is close to one, like `titel`.
+fn is_misspelled_metadata_key(key: &str) -> bool {
+    !KNOWN_METADATA_KEYS.contains(&key)
+        && KNOWN_METADATA_KEYS
+            .iter()
+            .any(|known| edit_distance(key, known, std::cmp::max(known.len() / 3, 1)).is_some())
+}
+
+/// Collect the `key: value` lines of `metadata` with a known key. `metadata` doesn't include the
+/// title.
+fn metadata_map<'a>(metadata: &[&'a str]) -> FxIndexMap<&'a str, &'a str> {
+    metadata
+        .iter()
+        .filter_map(|line| metadata_entry(line))
+        .filter(|(key, _)| KNOWN_METADATA_KEYS.contains(key))
+        .collect()
+}
+
+/// Build a `<script type="application/ld+json">` block describing the page as a schema.org
+/// `Article`. Metadata fields which aren't present are omitted.
+fn json_ld_article(title: &str, metadata: &FxIndexMap<&str, &str>) -> String {
+    let mut article = serde_json::Map::new();
+    article.insert("@context".to_owned(), "https://schema.org".into());
+    article.insert("@type".to_owned(), "Article".into());
+    article.insert("headline".to_owned(), title.into());
+    if let Some(&description) = metadata.get("description") {
+        article.insert("description".to_owned(), description.into());
+    }
+    if let Some(&author) = metadata.get("author") {
+        article
+            .insert("author".to_owned(), serde_json::json!({ "@type": "Person", "name": author }));
+    }
+    if let Some(&date) = metadata.get("date") {
+        article.insert("datePublished".to_owned(), date.into());
+    }
+    let json = serde_json::Value::Object(article).to_string();
+    // Don't let the metadata close the `<script>` tag.
+    format!(r#"<script type="application/ld+json">{}</script>"#, json.replace("</", r"<\/"))
+}
+
+/// Build the badge shown next to the title for the `status` metadata of the file. Statuses other
+/// than `draft`, `stable` and `deprecated` get the `status-unknown` class.
+fn status_badge(status: &str) -> String {
+    let class = match &*status.to_ascii_lowercase() {
+        "draft" => "draft",
+        "stable" => "stable",
+        "deprecated" => "deprecated",
+        _ => "unknown",
+    };
+    format!(r#"<span class="status status-{class}">{}</span>"#, Escape(status))
+}
+
+/// The MIME type of an image, from the extension of its path.
+fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match &*extension {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        _ => return None,
+    })
+}
+
+/// Read the images of `text` given by relative paths, resolved against `base`, into `data:` URIs
+/// keyed by their destination in `text`.
+fn inline_images(text: &str, base: &Path, limit: u64) -> Result<FxHashMap<String, String>, String> {
+    let mut images = FxHashMap::default();
+    for dest in markdown::local_image_paths(text) {
+        let path = base.join(&dest);
+        let Some(mime) = image_mime_type(&path) else {
+            return Err(format!("{path}: unsupported image type to inline", path = path.display()));
+        };
+        let size = std::fs::metadata(&path)
+            .map_err(|err| format!("{path}: {err}", path = path.display()))?
+            .len();
+        if size > limit {
+            return Err(format!(
+                "{path}: image is {size} bytes, larger than the limit of {limit} bytes for \
+                 inlined images (see `--markdown-inline-image-limit`)",
+                path = path.display(),
+            ));
+        }
+        let data =
+            std::fs::read(&path).map_err(|err| format!("{path}: {err}", path = path.display()))?;
+        images.insert(dest, format!("data:{mime};base64,{}", BASE64_STANDARD.encode(data)));
+    }
+    Ok(images)
+}
+
 /// Render `input` (e.g., "foo.md") into an HTML file in `output`
-/// (e.g., output = "bar" => "bar/foo.html").
+/// (e.g., output = "bar" => "bar/foo.html"), or to stdout with `-o -` and `-Z unstable-options`.
 ///
 /// Requires session globals to be available, for symbol interning.
 pub(crate) fn render<P: AsRef<Path>>(
     input: P,
     options: RenderOptions,
     edition: Edition,
+    dcx: &DiagCtxt,
 ) -> Result<(), String> {
-    if let Err(e) = create_dir_all(&options.output) {
-        return Err(format!("{output}: {e}", output = options.output.display()));
+    render_file(input.as_ref(), &options, edition, &mut IdMap::new(), &[], dcx)?;
+    Ok(())
+}
+
+/// Render each of `inputs` into its own HTML file in `output`, like `render`. The IDs of the
+/// headings are unique across all the files. With `--markdown-index`, also write an `index.html`
+/// page linking to each of them, in order.
+///
+/// Requires session globals to be available, for symbol interning.
+pub(crate) fn render_many(
+    inputs: &[PathBuf],
+    options: RenderOptions,
+    edition: Edition,
+    dcx: &DiagCtxt,
+) -> Result<(), String> {
+    if options.markdown_stdout {
+        return Err("cannot write several rendered Markdown files to stdout".to_owned());
+    }
+    let mut file_names = FxHashMap::default();
+    for input in inputs {
+        let file_name = Path::new(input.file_name().unwrap()).with_extension("html");
+        if options.markdown_index && file_name == Path::new("index.html") {
+            return Err(format!(
+                "{input}: cannot be rendered to `index.html` with `--markdown-index`",
+                input = input.display()
+            ));
+        }
+        if let Some(other) = file_names.insert(file_name, input) {
+            return Err(format!(
+                "{input}: would overwrite the rendered `{other}`",
+                input = input.display(),
+                other = other.display()
+            ));
+        }
+    }
+
+    let mut ids = IdMap::new();
+    let mut pages = Vec::with_capacity(inputs.len());
+    let rendered_inputs: Vec<_> = inputs.iter().map(|input| normalize_path(input)).collect();
+    for input in inputs {
+        pages.push(render_file(input, &options, edition, &mut ids, &rendered_inputs, dcx)?);
     }
+    check_markdown_links(inputs, &rendered_inputs, &pages, dcx);
+    if options.markdown_index {
+        let index: Vec<_> = inputs
+            .iter()
+            .zip(&pages)
+            .map(|(input, page)| {
+                (Path::new(input.file_name().unwrap()).with_extension("html"), page.title.clone())
+            })
+            .collect();
+        write_index(&index, &options)?;
+    }
+    Ok(())
+}
 
-    let input = input.as_ref();
-    let mut output = options.output;
-    output.push(input.file_name().unwrap());
-    output.set_extension("html");
+/// Render all of `inputs` into a single HTML page, written like the one of the first file (see
+/// `write_page`). Each file is a section of the page, with its headings one level lower, and the
+/// table of contents lists the sections with their headings. The page is titled
+/// `--markdown-combine-title`, or like the first file, whose metadata it also uses.
+///
+/// Requires session globals to be available, for symbol interning.
+pub(crate) fn render_combined(
+    inputs: &[PathBuf],
+    options: RenderOptions,
+    edition: Edition,
+    dcx: &DiagCtxt,
+) -> Result<(), String> {
+    if options.markdown_index {
+        return Err("`--markdown-index` cannot be used with `--markdown-combine`".to_owned());
+    }
+    check_output_options(&options)?;
+    let input_strs = inputs
+        .iter()
+        .map(|input| {
+            read_to_string(input).map_err(|err| format!("{input}: {err}", input = input.display()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let mut css = String::new();
-    for name in &options.markdown_css {
-        write!(css, r#"<link rel="stylesheet" href="{name}">"#)
-            .expect("Writing to a String can't fail");
+    let mut section_options = options.clone();
+    section_options.markdown_heading_offset = lower_heading_offset(options.markdown_heading_offset);
+    let mut ids = IdMap::new();
+    let mut sections = Vec::with_capacity(inputs.len());
+    for (input, input_str) in inputs.iter().zip(&input_strs) {
+        let base = input.parent().unwrap_or(Path::new(""));
+        // The links to the other files are left alone, as they don't have a page of their own.
+        let section = render_content(input_str, base, &section_options, edition, &mut ids, &[])?;
+        warn_unknown_metadata(input, &section.unknown_metadata, dcx);
+        sections.push(section);
+    }
+    let title = options.markdown_combine_title.as_deref();
+    let page = render_page(combine_contents(sections, title, &mut ids, &options), &options)?;
+    write_page(&inputs[0], &page, &options)
+}
+
+/// The heading offset one level below `offset`, down to `<h6>`.
+fn lower_heading_offset(offset: HeadingOffset) -> HeadingOffset {
+    match offset {
+        HeadingOffset::H1 => HeadingOffset::H2,
+        HeadingOffset::H2 => HeadingOffset::H3,
+        HeadingOffset::H3 => HeadingOffset::H4,
+        HeadingOffset::H4 => HeadingOffset::H5,
+        HeadingOffset::H5 | HeadingOffset::H6 => HeadingOffset::H6,
+    }
+}
+
+/// Merge the rendered `sections`, in order, into the content of a single page titled `title`, or
+/// like the first section, whose metadata it also takes. Each section starts with its title, in a
+/// heading at the level of `--markdown-heading-offset` which the back-to-top links of its own
+/// headings point to, and the sections are separated by a rule.
+fn combine_contents<'a>(
+    sections: Vec<RenderedContent<'a>>,
+    title: Option<&str>,
+    ids: &mut IdMap,
+    options: &RenderOptions,
+) -> RenderedContent<'a> {
+    let level = options.markdown_heading_offset as u32 + 1;
+    let words = sections.iter().map(|section| section.words).sum();
+    let mut html = String::new();
+    let mut toc_sections = Vec::with_capacity(sections.len());
+    let mut first = None;
+    for section in sections {
+        let id = section.top_id.unwrap_or_else(|| ids.derive(markdown::heading_id(&section.title)));
+        if !html.is_empty() {
+            html.push_str("<hr>");
+        }
+        write!(
+            html,
+            "<section><h{level} id=\"{id}\"><a class=\"doc-anchor\" href=\"#{id}\">§</a>\
+             {title}</h{level}>{content}</section>",
+            title = Escape(&section.title),
+            content = section.content,
+        )
+        .expect("Writing to a String can't fail");
+        toc_sections.push((Escape(&section.title).to_string(), id, section.toc));
+        first.get_or_insert((section.title, section.metadata, section.summary));
+    }
+    let (first_title, metadata, summary) = first.expect("at least one file is combined");
+    // Without `--markdown-no-toc`, every section has a table of contents.
+    let toc = toc_sections
+        .into_iter()
+        .map(|(name, id, toc)| Some((name, id, toc?)))
+        .collect::<Option<Vec<_>>>()
+        .map(Toc::from_sections);
+
+    RenderedContent {
+        title: title.map_or(first_title, str::to_owned),
+        metadata,
+        content: html,
+        toc,
+        top_id: None,
+        summary,
+        words,
+        markdown_links: Vec::new(),
+        unknown_metadata: Vec::new(),
     }
+}
 
+/// Warn about the links between `inputs` to an anchor which their target doesn't have. Links
+/// to files which aren't rendered along are not checked. `rendered_inputs` are the normalized
+/// `inputs` (see `normalize_path`).
+fn check_markdown_links(
+    inputs: &[PathBuf],
+    rendered_inputs: &[PathBuf],
+    pages: &[RenderedPage],
+    dcx: &DiagCtxt,
+) {
+    let anchors: Vec<FxHashSet<&str>> = pages.iter().map(|page| html_ids(&page.html)).collect();
+    for (input, page) in inputs.iter().zip(pages) {
+        let base = input.parent().unwrap_or(Path::new(""));
+        for (dest, line) in &page.markdown_links {
+            let Some((_, anchor)) = dest.split_once('#') else { continue };
+            let target = markdown_link_target(base, dest);
+            let Some(index) = rendered_inputs.iter().position(|other| *other == target) else {
+                continue;
+            };
+            if !anchors[index].contains(anchor) {
+                dcx.warn(format!(
+                    "{input}:{line}: `{dest}` links to an anchor which `{target}` doesn't have",
+                    input = input.display(),
+                    target = inputs[index].display(),
+                ));
+            }
+        }
+    }
+}
+
+/// `path` made absolute and without its `.` and `..` components, so that two paths to the same
+/// file compare equal. Symbolic links are not resolved.
+fn normalize_path(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(normalized.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// The normalized path of the file which the link `dest`, of a file in `base`, points to.
+fn markdown_link_target(base: &Path, dest: &str) -> PathBuf {
+    let path = dest.split_once('#').map_or(dest, |(path, _)| path);
+    normalize_path(&base.join(path))
+}
+
+/// The `id` attributes of the elements of `html`.
+fn html_ids(html: &str) -> FxHashSet<&str> {
+    html.match_indices(r#" id=""#)
+        .filter_map(|(start, attr)| {
+            let value = &html[start + attr.len()..];
+            value.find('"').map(|end| &value[..end])
+        })
+        .collect()
+}
+
+/// Write the `index.html` page of `--markdown-index`, linking to each of `pages` given by their
+/// file name and title.
+fn write_index(pages: &[(PathBuf, String)], options: &RenderOptions) -> Result<(), String> {
+    let css = stylesheet_links(&options.markdown_css);
+    let mut entries = String::new();
+    for (file_name, title) in pages {
+        write!(
+            entries,
+            r#"<li><a href="{href}">{title}</a></li>"#,
+            href = Escape(&file_name.to_string_lossy()),
+            title = Escape(title),
+        )
+        .expect("Writing to a String can't fail");
+    }
+
+    let mut page = String::new();
+    write!(
+        page,
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="{viewport}">
+    <meta name="generator" content="rustdoc">
+    <title>Index</title>
+    {css}
+    {in_header}
+</head>
+<body class="rustdoc">
+    {before_content}
+    <h1 class="title">Index</h1>
+    <ol class="index">{entries}</ol>
+    {after_content}
+</body>
+</html>"#,
+        lang = Escape(&options.markdown_lang),
+        viewport = Escape(&options.markdown_viewport),
+        css = css,
+        in_header = options.external_html.in_header,
+        before_content = options.external_html.before_content,
+        entries = entries,
+        after_content = options.external_html.after_content,
+    )
+    .expect("Writing to a String can't fail");
+
+    let output = options.output.join("index.html");
+    std::fs::write(&output, line_endings(page, options.markdown_crlf))
+        .map_err(|e| format!("cannot write to `{output}`: {e}", output = output.display()))
+}
+
+/// Render `input` with the IDs in `ids` and the links to `rendered_inputs`, and write it (see
+/// `write_page`), returning the rendered page (see `render_to_string`). The metadata lines with a
+/// misspelled key are reported through `dcx`.
+fn render_file(
+    input: &Path,
+    options: &RenderOptions,
+    edition: Edition,
+    ids: &mut IdMap,
+    rendered_inputs: &[PathBuf],
+    dcx: &DiagCtxt,
+) -> Result<RenderedPage, String> {
+    check_output_options(options)?;
     let input_str =
         read_to_string(input).map_err(|err| format!("{input}: {err}", input = input.display()))?;
-    let playground_url = options.markdown_playground_url.or(options.playground_url);
-    let playground = playground_url.map(|url| markdown::Playground { crate_name: None, url });
+    let base = input.parent().unwrap_or(Path::new(""));
+    let page = render_to_string(&input_str, base, options, edition, ids, rendered_inputs)?;
+    warn_unknown_metadata(input, &page.unknown_metadata, dcx);
+    write_page(input, &page, options)?;
+    Ok(page)
+}
+
+/// Check that the output options can be used together, before anything is rendered.
+fn check_output_options(options: &RenderOptions) -> Result<(), String> {
+    if options.markdown_stdout && options.markdown_toc_file {
+        return Err("`--markdown-toc-file` cannot be used when writing to stdout".to_owned());
+    }
+    if options.markdown_stdout && options.markdown_math.is_some() {
+        return Err("`--markdown-math` cannot be used when writing to stdout".to_owned());
+    }
+    Ok(())
+}
 
-    let mut out =
-        File::create(&output).map_err(|e| format!("{output}: {e}", output = output.display()))?;
+/// Report the metadata lines of `input` with a misspelled key.
+fn warn_unknown_metadata(input: &Path, unknown_metadata: &[(usize, String)], dcx: &DiagCtxt) {
+    for (line, metadata) in unknown_metadata {
+        dcx.warn(format!(
+            "{input}:{line}: unknown metadata key in `{metadata}`",
+            input = input.display()
+        ));
+    }
+}
 
-    let (metadata, text) = extract_leading_metadata(&input_str);
-    if metadata.is_empty() {
-        return Err("invalid markdown file: no initial lines starting with `# ` or `%`".to_owned());
+/// Write `page`, rendered from `input`, to its HTML file in `output` (e.g., output = "bar" =>
+/// "bar/foo.html" for "foo.md"), or to stdout with `-o -`. Also write its table of contents with
+/// `--markdown-toc-file`, and the MathJax configuration with `--markdown-math`.
+fn write_page(input: &Path, page: &RenderedPage, options: &RenderOptions) -> Result<(), String> {
+    let to_stdout = options.markdown_stdout;
+    if !to_stdout && let Err(e) = create_dir_all(&options.output) {
+        return Err(format!("{output}: {e}", output = options.output.display()));
     }
-    let title = metadata[0];
 
-    let mut ids = IdMap::new();
+    let output = if to_stdout {
+        PathBuf::from("<stdout>")
+    } else {
+        let mut output = options.output.clone();
+        output.push(input.file_name().unwrap());
+        output.set_extension("html");
+        output
+    };
+
+    // The output file is only created once the page is rendered, so that a failed render doesn't
+    // leave an empty file behind.
+    let mut out: Box<dyn Write> = if to_stdout {
+        Box::new(io::stdout().lock())
+    } else {
+        let file = File::create(&output)
+            .map_err(|e| format!("{output}: {e}", output = output.display()))?;
+        Box::new(file)
+    };
+    if let Err(e) = out.write_all(page.html.as_bytes()).and_then(|()| out.flush()) {
+        return Err(format!("cannot write to `{output}`: {e}", output = output.display()));
+    }
+
+    if let Some(toc) = page.toc.as_ref().filter(|_| options.markdown_toc_file) {
+        let toc_output = output.with_extension("toc.html");
+        let toc = line_endings(format!("<nav id=\"TOC\">{toc}</nav>\n"), options.markdown_crlf);
+        std::fs::write(&toc_output, toc).map_err(|e| {
+            format!("cannot write to `{output}`: {e}", output = toc_output.display())
+        })?;
+    }
+    if options.markdown_math.is_some() {
+        let config_output = options.output.join(MATHJAX_CONFIG_FILE);
+        std::fs::write(
+            &config_output,
+            line_endings(MATHJAX_CONFIG.to_owned(), options.markdown_crlf),
+        )
+        .map_err(|e| {
+            format!("cannot write to `{output}`: {e}", output = config_output.display())
+        })?;
+    }
+    Ok(())
+}
+
+/// A Markdown file rendered by `render_to_string`.
+pub(crate) struct RenderedPage {
+    html: String,
+    title: String,
+    /// The table of contents, unless `--markdown-no-toc` is passed.
+    toc: Option<String>,
+    /// The links to relative `.md` files, with the line of the file where each one starts.
+    markdown_links: Vec<(String, usize)>,
+    /// The line number and content of the metadata lines whose key looks like a misspelled one of
+    /// `KNOWN_METADATA_KEYS`.
+    unknown_metadata: Vec<(usize, String)>,
+}
+
+/// The Markdown of a file rendered by `render_content`, before it is laid out into a page by
+/// `render_page`.
+struct RenderedContent<'a> {
+    title: String,
+    /// The known metadata of the file, from its frontmatter and `%` lines.
+    metadata: FxIndexMap<&'a str, &'a str>,
+    /// The rendered Markdown, without the table of contents.
+    content: String,
+    /// The table of contents, unless `--markdown-no-toc` is passed.
+    toc: Option<Toc>,
+    /// With `--markdown-back-to-top`, the ID of the title, which each heading links to.
+    top_id: Option<String>,
+    /// Without `description` metadata, the start of the first paragraph, which describes the page.
+    summary: String,
+    /// The number of words, with `--markdown-reading-time`.
+    words: usize,
+    markdown_links: Vec<(String, usize)>,
+    unknown_metadata: Vec<(usize, String)>,
+}
+
+/// Render the Markdown file `input_str` into an HTML page in memory, with the IDs in `ids`, without
+/// writing any file: `render_file` writes the result. The relative images of
+/// `--markdown-inline-images` are still read from `base`, and the program of `--markdown-filter`
+/// is run. The links to other `.md` files are resolved against `base` too, and the ones to
+/// `rendered_inputs`, normalized by `normalize_path`, point to the `.html` files they are
+/// rendered to.
+///
+/// Requires session globals to be available, for symbol interning.
+pub(crate) fn render_to_string(
+    input_str: &str,
+    base: &Path,
+    options: &RenderOptions,
+    edition: Edition,
+    ids: &mut IdMap,
+    rendered_inputs: &[PathBuf],
+) -> Result<RenderedPage, String> {
+    render_page(render_content(input_str, base, options, edition, ids, rendered_inputs)?, options)
+}
+
+/// Render the Markdown of `input_str`, like `render_to_string`, without laying it out into a page.
+fn render_content<'a>(
+    input_str: &'a str,
+    base: &Path,
+    options: &RenderOptions,
+    edition: Edition,
+    ids: &mut IdMap,
+    rendered_inputs: &[PathBuf],
+) -> Result<RenderedContent<'a>, String> {
+    let playground_url =
+        options.markdown_playground_url.clone().or_else(|| options.playground_url.clone());
+    let playground = playground_url.map(|url| markdown::Playground { crate_name: None, url });
+
+    // The frontmatter takes precedence over the `%` lines which may follow it.
+    let (frontmatter, body) =
+        extract_frontmatter(input_str).unwrap_or_else(|| (FxIndexMap::default(), input_str));
+    let (metadata, text) = extract_leading_metadata(body);
+    // `text` is the end of `input_str`, so the lines before it are the metadata.
+    let metadata_lines = input_str[..input_str.len() - text.len()].matches('\n').count();
+    let markdown_links: Vec<_> = markdown::markdown_file_links(text)
+        .into_iter()
+        .map(|(dest, line)| (dest, line + metadata_lines))
+        .collect();
+    let rendered_links = markdown_links
+        .iter()
+        .filter(|(dest, _)| rendered_inputs.contains(&markdown_link_target(base, dest)))
+        .filter_map(|(dest, _)| Some((dest.clone(), markdown::markdown_link_to_html(dest)?)))
+        .collect();
+    // Likewise, the lines before `body` are the frontmatter, and the title comes right after.
+    let frontmatter_lines = input_str[..input_str.len() - body.len()].matches('\n').count();
+    // The first `%` line is the title, unless the frontmatter has one.
+    let title_lines = usize::from(!frontmatter.contains_key("title"));
+    let unknown_metadata = metadata
+        .iter()
+        .enumerate()
+        .skip(title_lines)
+        .filter(|(_, line)| {
+            metadata_entry(line).is_some_and(|(key, _)| is_misspelled_metadata_key(key))
+        })
+        .map(|(index, line)| (frontmatter_lines + index + 1, line.to_string()))
+        .collect();
+    let Some(title) = frontmatter.get("title").or(metadata.first()).copied() else {
+        let mut err =
+            "invalid markdown file: no initial lines starting with `# ` or `%`".to_owned();
+        if let Some(suggestion) = near_miss_title(body) {
+            err.push_str(&format!("; did you mean `{suggestion}` with a space?"));
+        }
+        return Err(err);
+    };
+    let mut metadata_map = metadata_map(&metadata[title_lines..]);
+    metadata_map.extend(frontmatter);
+
+    // Without a `description` metadata line, the description is the start of the first paragraph.
+    let summary = match metadata_map.get("description") {
+        Some(_) => String::new(),
+        None => truncate_description(markdown::first_paragraph_text(text)),
+    };
+    let words =
+        if options.markdown_reading_time.is_some() { markdown::word_count(text) } else { 0 };
+
+    let inline_images = if options.markdown_inline_images {
+        inline_images(text, base, options.markdown_inline_image_limit)?
+    } else {
+        FxHashMap::default()
+    };
+
     let error_codes = ErrorCodes::from(options.unstable_features.is_nightly_build());
-    let text = if !options.markdown_no_toc {
-        MarkdownWithToc {
+    // Markdown files have no crate attributes to enable the feature with, so custom code classes
+    // are allowed wherever unstable features are.
+    let custom_code_classes_in_docs = options.unstable_features.is_nightly_build();
+    // Reserve the ID of the title before the headings get theirs.
+    let top_id = options.markdown_back_to_top.then(|| ids.derive("top"));
+    let standalone = Some(StandaloneOptions {
+        upgrade_insecure_links: options.markdown_upgrade_insecure,
+        autolink_bare_urls: options.markdown_autolink,
+        toc_depth: options.markdown_toc_depth,
+        toc_sidebar: options.markdown_toc_sidebar,
+        inline_images,
+        allowed_html_tags: options.markdown_allowed_html_tags.clone(),
+        trim_code_whitespace: options.markdown_trim_code_whitespace,
+        issue_url: options.markdown_issue_url.clone(),
+        back_to_top: top_id.clone(),
+        heading_attributes: options.markdown_heading_attributes,
+        markdown_links: rendered_links,
+    });
+    let (content, toc) = if !options.markdown_no_toc {
+        let (content, toc) = MarkdownWithToc {
             content: text,
-            ids: &mut ids,
+            ids,
             error_codes,
             edition,
             playground: &playground,
-            // For markdown files, it'll be disabled until the feature is enabled by default.
-            custom_code_classes_in_docs: false,
+            heading_offset: options.markdown_heading_offset,
+            custom_code_classes_in_docs,
+            standalone: &standalone,
         }
-        .into_string()
+        .into_string_without_toc();
+        (content, Some(toc))
     } else {
-        Markdown {
+        let content = Markdown {
             content: text,
             links: &[],
-            ids: &mut ids,
+            ids,
             error_codes,
             edition,
             playground: &playground,
-            heading_offset: HeadingOffset::H1,
-            // For markdown files, it'll be disabled until the feature is enabled by default.
-            custom_code_classes_in_docs: false,
+            heading_offset: options.markdown_heading_offset,
+            custom_code_classes_in_docs,
+            standalone: &standalone,
         }
-        .into_string()
+        .into_string();
+        (content, None)
     };
 
-    let err = write!(
-        &mut out,
-        r#"<!DOCTYPE html>
-<html lang="en">
+    Ok(RenderedContent {
+        title: title.to_owned(),
+        metadata: metadata_map,
+        content,
+        toc,
+        top_id,
+        summary,
+        words,
+        markdown_links,
+        unknown_metadata,
+    })
+}
+
+/// Lay the rendered `content` out into an HTML page, run through `--markdown-filter`.
+fn render_page(
+    content: RenderedContent<'_>,
+    options: &RenderOptions,
+) -> Result<RenderedPage, String> {
+    let RenderedContent {
+        title,
+        metadata: metadata_map,
+        content,
+        toc,
+        top_id,
+        summary,
+        words,
+        markdown_links,
+        unknown_metadata,
+    } = content;
+    let title = title.as_str();
+
+    // The highlighting colors come first, then the global stylesheets, then the ones of the `css`
+    // metadata, so that each can override the ones before.
+    let mut css = match &options.markdown_highlight_theme {
+        Some(theme) => format!("<style>{}</style>", highlight_css(theme)?),
+        None => String::new(),
+    };
+    css.push_str(&stylesheet_links(&options.markdown_css));
+    if let Some(names) = metadata_map.get("css") {
+        for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            write!(css, r#"<link rel="stylesheet" href="{}">"#, Escape(name))
+                .expect("Writing to a String can't fail");
+        }
+    }
+
+    let csp = match &options.markdown_csp {
+        Some(policy) => {
+            format!(r#"<meta http-equiv="Content-Security-Policy" content="{}">"#, Escape(policy))
+        }
+        None => String::new(),
+    };
+    let toc_sidebar_css = if options.markdown_toc_sidebar {
+        format!("<style>{TOC_SIDEBAR_CSS}</style>")
+    } else {
+        String::new()
+    };
+    let favicon = options.markdown_favicon.as_deref().or(metadata_map.get("favicon").copied());
+    let favicon = match favicon {
+        Some(url) => format!(r#"<link rel="icon" href="{}">"#, Escape(url)),
+        None => String::new(),
+    };
+    let theme_color = match &options.markdown_theme_color {
+        Some(color) => format!(r#"<meta name="theme-color" content="{}">"#, Escape(color)),
+        None => String::new(),
+    };
+    let math = match &options.markdown_math {
+        Some(url) => format!(
+            "<script src=\"{MATHJAX_CONFIG_FILE}\"></script>\
+             <script defer src=\"{}\"></script>",
+            Escape(url)
+        ),
+        None => String::new(),
+    };
+    let canonical = match metadata_map.get("canonical") {
+        Some(&url) => format!(r#"<link rel="canonical" href="{}">"#, Escape(url)),
+        None => String::new(),
+    };
+    let meta = ["author", "date", "description"]
+        .into_iter()
+        .filter_map(|name| {
+            let content = match name {
+                "description" if !summary.is_empty() => summary.as_str(),
+                _ => *metadata_map.get(name)?,
+            };
+            Some(format!(r#"<meta name="{name}" content="{}">"#, Escape(content)))
+        })
+        .collect::<String>();
+    let json_ld = if options.markdown_json_ld {
+        json_ld_article(title, &metadata_map)
+    } else {
+        String::new()
+    };
+
+    // `--markdown-banner-html` only trusts the banner given on the command line, not the one of
+    // the file.
+    let banner = match (options.markdown_banner.as_deref(), metadata_map.get("banner").copied()) {
+        (Some(banner), _) if options.markdown_banner_html => {
+            format!(r#"<div class="banner">{banner}</div>"#)
+        }
+        (Some(banner), _) | (None, Some(banner)) => {
+            format!(r#"<div class="banner">{}</div>"#, Escape(banner))
+        }
+        (None, None) => String::new(),
+    };
+
+    let status = match metadata_map.get("status") {
+        Some(&status) => status_badge(status),
+        None => String::new(),
+    };
+
+    let reading_time = match options.markdown_reading_time {
+        Some(wpm) => {
+            let minutes = words.div_ceil(wpm as usize).max(1);
+            format!(r#"<p class="reading-time">~{minutes} min read</p>"#)
+        }
+        None => String::new(),
+    };
+
+    let toc = toc.map(|toc| toc.print());
+    let text = match &toc {
+        Some(toc) => markdown::with_toc(content, toc, options.markdown_toc_sidebar),
+        None => content,
+    };
+    let lang = Escape(metadata_map.get("lang").copied().unwrap_or(&options.markdown_lang));
+    let top_id = top_id.map(|id| format!(r#" id="{id}""#)).unwrap_or_default();
+    let page = match &options.markdown_template {
+        // Each placeholder of a template is replaced by the same part as in the built-in page:
+        //
+        // - `{{title}}`, `{{lang}}` and `{{viewport}}`: the escaped title, language and viewport.
+        // - `{{meta}}`, `{{theme_color}}`, `{{csp}}`, `{{favicon}}`, `{{css}}`, `{{math}}`,
+        //   `{{canonical}}`, `{{json_ld}}` and `{{in_header}}`: the tags of the `<head>`, which
+        //   are empty when the option or metadata they come from is not given.
+        // - `{{before_content}}`, `{{banner}}`, `{{status}}`, `{{reading_time}}`, `{{content}}`
+        //   and `{{after_content}}`: the parts of the `<body>`.
+        // - `{{top_id}}`: the ` id="..."` attribute of the title with `--markdown-back-to-top`,
+        //   which the links of the headings point to.
+        Some(template) => fill_template(
+            template,
+            &[
+                ("title", Escape(title).to_string().as_str()),
+                ("lang", lang.to_string().as_str()),
+                ("viewport", Escape(&options.markdown_viewport).to_string().as_str()),
+                ("meta", meta.as_str()),
+                ("theme_color", theme_color.as_str()),
+                ("csp", csp.as_str()),
+                ("favicon", favicon.as_str()),
+                ("css", format!("{toc_sidebar_css}{css}").as_str()),
+                ("math", math.as_str()),
+                ("canonical", canonical.as_str()),
+                ("json_ld", json_ld.as_str()),
+                ("in_header", options.external_html.in_header.as_str()),
+                ("before_content", options.external_html.before_content.as_str()),
+                ("banner", banner.as_str()),
+                ("top_id", top_id.as_str()),
+                ("status", status.as_str()),
+                ("reading_time", reading_time.as_str()),
+                ("content", text.as_str()),
+                ("after_content", options.external_html.after_content.as_str()),
+            ],
+        ),
+        None => {
+            let mut page = String::new();
+            write!(
+                page,
+                r#"<!DOCTYPE html>
+<html lang="{lang}">
 <head>
     <meta charset="utf-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="viewport" content="{viewport}">
     <meta name="generator" content="rustdoc">
+    {meta}
+    {theme_color}
+    {csp}
     <title>{title}</title>
+    {favicon}
 
+    {toc_sidebar_css}
     {css}
+    {math}
+    {canonical}
+    {json_ld}
     {in_header}
 </head>
 <body class="rustdoc">
@@ -123,23 +991,159 @@ pub(crate) fn render<P: AsRef<Path>>(
     <![endif]-->
 
     {before_content}
-    <h1 class="title">{title}</h1>
+    {banner}
+    <h1 class="title"{top_id}>{title}</h1>
+    {status}
+    {reading_time}
     {text}
     {after_content}
 </body>
 </html>"#,
-        title = Escape(title),
-        css = css,
-        in_header = options.external_html.in_header,
-        before_content = options.external_html.before_content,
-        text = text,
-        after_content = options.external_html.after_content,
-    );
+                lang = lang,
+                title = Escape(title),
+                viewport = Escape(&options.markdown_viewport),
+                meta = meta,
+                theme_color = theme_color,
+                csp = csp,
+                favicon = favicon,
+                toc_sidebar_css = toc_sidebar_css,
+                css = css,
+                math = math,
+                canonical = canonical,
+                json_ld = json_ld,
+                in_header = options.external_html.in_header,
+                before_content = options.external_html.before_content,
+                banner = banner,
+                top_id = top_id,
+                status = status,
+                reading_time = reading_time,
+                text = text,
+                after_content = options.external_html.after_content,
+            )
+            .expect("Writing to a String can't fail");
+            page
+        }
+    };
 
-    match err {
-        Err(e) => Err(format!("cannot write to `{output}`: {e}", output = output.display())),
-        Ok(_) => Ok(()),
+    let page = match &options.markdown_filter {
+        Some(program) => run_filter(program, &options.markdown_filter_args, page)?,
+        None => page,
+    };
+    let html = line_endings(page, options.markdown_crlf);
+    Ok(RenderedPage { html, title: title.to_owned(), toc, markdown_links, unknown_metadata })
+}
+
+/// The placeholders which a `--markdown-template` must contain.
+pub(crate) const REQUIRED_TEMPLATE_PLACEHOLDERS: &[&str] = &["{{title}}", "{{content}}"];
+
+/// Replace the `{{name}}` placeholders of `template` by their value in `values`, in a single
+/// pass, so that the values themselves are never searched for placeholders. Unknown placeholders
+/// are kept as they are.
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut page = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        page.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest[2..].split_once("}}").and_then(|(name, _)| {
+            values.iter().find(|&&(key, _)| key == name).map(|&(_, value)| (name, value))
+        });
+        match value {
+            Some((name, value)) => {
+                page.push_str(value);
+                rest = &rest[name.len() + 4..];
+            }
+            None => {
+                page.push_str("{{");
+                rest = &rest[2..];
+            }
+        }
+    }
+    page.push_str(rest);
+    page
+}
+
+/// Shorten `text` to at most `MAX_DESCRIPTION_LEN` characters, cutting it at a word boundary and
+/// ending it with an ellipsis if it is longer.
+fn truncate_description(text: String) -> String {
+    if text.chars().count() <= MAX_DESCRIPTION_LEN {
+        return text;
+    }
+    // Keep room for the ellipsis.
+    let (end, _) = text.char_indices().nth(MAX_DESCRIPTION_LEN - 1).unwrap();
+    let cut = &text[..end];
+    let cut = cut.rsplit_once(' ').map_or(cut, |(words, _)| words);
+    format!("{}…", cut.trim_end())
+}
+
+/// The CSS which colors highlighted Rust code like the bundled `theme` does, taken from
+/// `rustdoc.css` so that rendered Markdown files match the documentation of crates. Fails if
+/// `rustdoc.css` no longer has the highlighting rules or the colors of `theme` where expected.
+fn highlight_css(theme: &str) -> Result<String, String> {
+    let css = std::str::from_utf8(static_files::STATIC_FILES.rustdoc_css.bytes).unwrap();
+    let error = |what: &str| format!("cannot find {what} in the bundled `rustdoc.css`");
+    let (_, rules) = css
+        .split_once("/* Code highlighting */")
+        .ok_or_else(|| error("the code highlighting rules"))?;
+    let rules = rules.split("\n\n").next().unwrap_or_default().trim();
+    let (_, theme_rules) = css
+        .split_once(&format!(r#"[data-theme="{theme}"]"#))
+        .ok_or_else(|| error(&format!("the `{theme}` theme")))?;
+    let colors: String = theme_rules
+        .split('}')
+        .next()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("--code-highlight-"))
+        .collect();
+    if colors.is_empty() {
+        return Err(error(&format!("the highlighting colors of the `{theme}` theme")));
     }
+    Ok(format!(":root {{{colors}}}\n{rules}"))
+}
+
+/// The `<link>` tags of the `--markdown-css` stylesheets.
+fn stylesheet_links(names: &[String]) -> String {
+    let mut css = String::new();
+    for name in names {
+        write!(css, r#"<link rel="stylesheet" href="{name}">"#)
+            .expect("Writing to a String can't fail");
+    }
+    css
+}
+
+/// Use CRLF line endings in `html` if `crlf` is set.
+fn line_endings(html: String, crlf: bool) -> String {
+    if !crlf {
+        return html;
+    }
+    // Normalize first, so that line endings which are already CRLF are not doubled.
+    html.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+/// Pipe `html` through the `--markdown-filter` program, returning what it writes to its stdout.
+fn run_filter(program: &str, args: &[String], html: String) -> Result<String, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("cannot run markdown filter `{program}`: {e}"))?;
+    // Feed the filter from another thread, so that a filter which writes its output before it
+    // has read all of its input can't deadlock with us.
+    let mut stdin = child.stdin.take().expect("the filter's stdin is piped");
+    let writer = thread::spawn(move || stdin.write_all(html.as_bytes()));
+    let filtered =
+        child.wait_with_output().map_err(|e| format!("markdown filter `{program}` failed: {e}"))?;
+    let written = writer.join().expect("writing to the markdown filter panicked");
+    // A filter which fails may not read all of its input, so its status is the better error.
+    if !filtered.status.success() {
+        return Err(format!("markdown filter `{program}` failed: {}", filtered.status));
+    }
+    written.map_err(|e| format!("cannot write to markdown filter `{program}`: {e}"))?;
+    String::from_utf8(filtered.stdout)
+        .map_err(|_| format!("markdown filter `{program}` did not write valid UTF-8"))
 }
 
 /// Runs any tests/code examples in the markdown file `input`.
@@ -172,17 +1176,50 @@ pub(crate) fn test(options: Options) -> Result<(), String> {
     );
     collector.set_position(DUMMY_SP);
     let codes = ErrorCodes::from(options.unstable_features.is_nightly_build());
+    // As in `render`, there is no crate attribute to enable custom code classes with.
+    let custom_code_classes_in_docs = options.unstable_features.is_nightly_build();
 
-    // For markdown files, custom code classes will be disabled until the feature is enabled by default.
     find_testable_code(
         &input_str,
         &mut collector,
         codes,
         options.enable_per_target_ignores,
         None,
-        false,
+        custom_code_classes_in_docs,
     );
 
-    crate::doctest::run_tests(options.test_args, options.nocapture, collector.tests);
+    // `--markdown-test-line` selects the doctests by their line.
+    let input_path = options.input.opt_path();
+    let lines = options
+        .markdown_test_lines
+        .iter()
+        .map(|arg| {
+            line_selector(arg, input_path).ok_or_else(|| {
+                format!(
+                    "`--markdown-test-line` expects `[<file>:]<line>` of the tested file, \
+                     found `{arg}`"
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut tests = collector.tests;
+    if !lines.is_empty() {
+        tests.retain(|test| {
+            let name = test.desc.name.as_slice();
+            lines.iter().any(|line| name.ends_with(&format!("(line {line})")))
+        });
+    }
+    crate::doctest::run_tests(options.test_args, options.nocapture, tests);
     Ok(())
 }
+
+/// The line selected by a `<file>:<line>` argument of `--markdown-test-line`, if it is one for
+/// `input`. The file can be left out, as in `<line>` or `:<line>`.
+fn line_selector(arg: &str, input: Option<&Path>) -> Option<usize> {
+    let (file, line) = arg.rsplit_once(':').unwrap_or(("", arg));
+    let line = line.parse().ok()?;
+    (file.is_empty() || input.is_some_and(|input| input.ends_with(file))).then_some(line)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,137 @@
+use super::{
+    combine_contents, lower_heading_offset, normalize_path, render_content, render_page,
+    render_to_string,
+};
+use crate::config::{Options, RenderOptions};
+use crate::html::markdown::IdMap;
+use rustc_session::config::ErrorOutputType;
+use rustc_session::{getopts, EarlyDiagCtxt};
+use rustc_span::create_default_session_globals_then;
+use rustc_span::edition::{Edition, DEFAULT_EDITION};
+use std::path::Path;
+
+/// Render `input_str` on its own, like `rustdoc input.md` would.
+fn render(input_str: &str, options: &RenderOptions, edition: Edition) -> Result<String, String> {
+    let page =
+        render_to_string(input_str, Path::new(""), options, edition, &mut IdMap::new(), &[])?;
+    Ok(page.html)
+}
+
+/// The render options of `rustdoc input.md` followed by `args`.
+fn render_options(args: &[&str]) -> RenderOptions {
+    let mut options = getopts::Options::new();
+    for option in crate::opts() {
+        (option.apply)(&mut options);
+    }
+    let args: Vec<_> = ["input.md"].iter().chain(args).map(|arg| arg.to_string()).collect();
+    let matches = options.parse(&args).unwrap();
+    let mut early_dcx = EarlyDiagCtxt::new(ErrorOutputType::default());
+    Options::from_matches(&mut early_dcx, &matches, args).unwrap().1
+}
+
+#[test]
+fn test_render_to_string() {
+    create_default_session_globals_then(|| {
+        let options = render_options(&["-o", "no-such-directory"]);
+        let html = render("% The Title\n\n# A heading\n\nSome text.\n", &options, DEFAULT_EDITION)
+            .unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"), "{html}");
+        assert!(html.contains("<title>The Title</title>"), "{html}");
+        assert!(html.contains("<p>Some text.</p>"), "{html}");
+        // Nothing is written, not even the output directory.
+        assert!(!options.output.exists());
+
+        let err = render("Some text.\n", &options, DEFAULT_EDITION).unwrap_err();
+        assert!(err.starts_with("invalid markdown file: no initial lines"), "{err}");
+    });
+}
+
+#[test]
+fn test_markdown_links_to_rendered_inputs() {
+    create_default_session_globals_then(|| {
+        let options = render_options(&[]);
+        let input = "% Title\n\n[a](a.md) [b](./sub/../b.md#x) [c](c.md#x) [d](sub/d.md)\n";
+        let rendered_inputs =
+            [normalize_path(Path::new("a.md")), normalize_path(Path::new("./b.md"))];
+        let html = render_to_string(
+            input,
+            Path::new(""),
+            &options,
+            DEFAULT_EDITION,
+            &mut IdMap::new(),
+            &rendered_inputs,
+        )
+        .unwrap()
+        .html;
+        assert!(html.contains(r#"<a href="a.html">a</a>"#), "{html}");
+        assert!(html.contains(r#"<a href="./sub/../b.html#x">b</a>"#), "{html}");
+        // The links to files which aren't rendered along are left alone.
+        assert!(html.contains(r#"<a href="c.md#x">c</a>"#), "{html}");
+        assert!(html.contains(r#"<a href="sub/d.md">d</a>"#), "{html}");
+    });
+}
+
+#[test]
+fn test_unknown_metadata() {
+    create_default_session_globals_then(|| {
+        let options = render_options(&[]);
+        let input = "% Title\n% 2024-01-01 10:00\n% https://example.com\n% titel: A Typo\n\
+                     % See also: the book\n% author: Me\n\nText.\n";
+        let page = render_to_string(
+            input,
+            Path::new(""),
+            &options,
+            DEFAULT_EDITION,
+            &mut IdMap::new(),
+            &[],
+        )
+        .unwrap();
+        // Only the misspelled key is reported; the lines which merely contain a colon are text.
+        assert_eq!(page.unknown_metadata, [(4, "titel: A Typo".to_owned())]);
+        assert!(page.html.contains(r#"<meta name="author" content="Me">"#), "{}", page.html);
+    });
+}
+
+#[test]
+fn test_combine_contents() {
+    create_default_session_globals_then(|| {
+        let options = render_options(&[]);
+        let mut section_options = options.clone();
+        section_options.markdown_heading_offset =
+            lower_heading_offset(options.markdown_heading_offset);
+        let mut ids = IdMap::new();
+        let inputs = ["% First\n% lang: fr\n\n# Usage\n\nOne.\n", "% Second\n\n# Usage\n\nTwo.\n"];
+        let sections = inputs
+            .into_iter()
+            .map(|input_str| {
+                let base = Path::new("");
+                render_content(input_str, base, &section_options, DEFAULT_EDITION, &mut ids, &[])
+                    .unwrap()
+            })
+            .collect();
+        let content = combine_contents(sections, None, &mut ids, &options);
+        let html = render_page(content, &options).unwrap().html;
+        // The page is titled like the first file, and uses its metadata.
+        assert!(html.contains("<title>First</title>"), "{html}");
+        assert!(html.contains(r#"<html lang="fr">"#), "{html}");
+        // Both files are there, with their headings one level lower and unique IDs.
+        assert!(
+            html.contains(
+                "<section><h2 id=\"first\"><a class=\"doc-anchor\" href=\"#first\">§</a>First</h2>"
+            ),
+            "{html}"
+        );
+        assert!(html.contains(r#"<h3 id="usage">"#), "{html}");
+        assert!(html.contains("<p>One.</p>"), "{html}");
+        assert!(html.contains("<hr><section><h2 id=\"second\">"), "{html}");
+        assert!(html.contains(r#"<h3 id="usage-1">"#), "{html}");
+        assert!(html.contains("<p>Two.</p>"), "{html}");
+        // A single table of contents lists the files with their headings.
+        let toc = "<nav id=\"TOC\"><ul>\n<li><a href=\"#first\">1 First</a><ul>\n\
+                   <li><a href=\"#usage\">1.1 Usage</a><ul></ul></li></ul></li>\n\
+                   <li><a href=\"#second\">2 Second</a><ul>\n\
+                   <li><a href=\"#usage-1\">2.1 Usage</a><ul></ul></li></ul></li></ul></nav>";
+        assert!(html.contains(toc), "{html}");
+        assert_eq!(html.matches("<nav id=\"TOC\">").count(), 1, "{html}");
+    });
+}
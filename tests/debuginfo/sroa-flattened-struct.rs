@@ -0,0 +1,51 @@
+//@ compile-flags:-g -Zmir-sroa=yes
+//@ min-lldb-version: 310
+
+// Checks that a struct local flattened by SROA can still be printed as a whole: its debuginfo is
+// made of one fragment per field, which the debugger reassembles. LLVM is not optimizing here, so
+// that the fragments are not optimized out.
+
+// === GDB TESTS ===================================================================================
+
+// gdb-command:run
+
+// gdb-command:print point
+// gdbg-check:$1 = {x = 10, y = 23.5}
+// gdbr-check:$1 = sroa_flattened_struct::Point {x: 10, y: 23.5}
+
+// gdb-command:print point.y
+// gdb-check:$2 = 23.5
+
+// === LLDB TESTS ==================================================================================
+
+// lldb-command:run
+
+// lldb-command:v point
+// lldbg-check:[...] { x = 10 y = 23.5 }
+// lldbr-check:(sroa_flattened_struct::Point) point = (x = 10, y = 23.5)
+
+// lldb-command:v point.y
+// lldbg-check:[...] 23.5
+// lldbr-check:(f64) point.y = 23.5
+
+#![allow(unused_variables)]
+#![feature(omit_gdb_pretty_printer_section)]
+#![omit_gdb_pretty_printer_section]
+
+struct Point {
+    x: i32,
+    y: f64,
+}
+
+#[inline(never)]
+fn flattened(x: i32, y: f64) -> f64 {
+    let point = Point { x, y };
+    zzz(); // #break
+    point.x as f64 + point.y
+}
+
+fn main() {
+    flattened(10, 23.5);
+}
+
+fn zzz() {()}
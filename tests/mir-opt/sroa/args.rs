@@ -0,0 +1,28 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-args
+//@ no-prefer-dynamic
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that with `-Zmir-sroa-args`, an argument whose fields are only read is flattened, and its
+/// fields are moved into the fragments at the start of the body.
+pub fn swap(p: Pair) -> (u32, u32) {
+    // CHECK-LABEL: fn swap(
+    // CHECK: debug ((p: Pair).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((p: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK-NEXT: [[a]] = move (_1.0: u32);
+    // CHECK-NEXT: [[b]] = move (_1.1: u32);
+    // CHECK-NOT: _1
+    // CHECK: return;
+    (p.b, p.a)
+}
+
+fn main() {
+    swap(Pair { a: 1, b: 2 });
+}
+
+// EMIT_MIR args.swap.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,68 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+/// Check that an array of tuples which is only accessed with constant indices is flattened into
+/// one local per element, and then into one local per element and field.
+pub fn tuples(x: u32, y: u32) -> (u32, u32, u32, u32) {
+    // CHECK-LABEL: fn tuples(
+
+    // CHECK: [[array:_[0-9]+]]: [(u32, u32); 2];
+    // CHECK: debug ((arr: [(u32, u32); 2])[0 of 2].0: u32) => [[first_0:_[0-9]+]];
+    // CHECK: debug ((arr: [(u32, u32); 2])[0 of 2].1: u32) => [[first_1:_[0-9]+]];
+    // CHECK: debug ((arr: [(u32, u32); 2])[1 of 2].0: u32) => [[second_0:_[0-9]+]];
+    // CHECK: debug ((arr: [(u32, u32); 2])[1 of 2].1: u32) => [[second_1:_[0-9]+]];
+
+    // CHECK: bb0: {
+    // CHECK-NOT: [[array]]
+    // CHECK: StorageLive([[first_0]]);
+    // CHECK: StorageLive([[first_1]]);
+    // CHECK: StorageLive([[second_0]]);
+    // CHECK: StorageLive([[second_1]]);
+    // CHECK: [[first_0]] = move ({{_[0-9]+}}.0: u32);
+    // CHECK: [[first_1]] = move ({{_[0-9]+}}.1: u32);
+    // CHECK: [[second_0]] = move ({{_[0-9]+}}.0: u32);
+    // CHECK: [[second_1]] = move ({{_[0-9]+}}.1: u32);
+    // CHECK: = [[first_0]];
+    // CHECK: = [[first_1]];
+    // CHECK: = [[second_0]];
+    // CHECK: = [[second_1]];
+    // CHECK: StorageDead([[first_0]]);
+    // CHECK: StorageDead([[first_1]]);
+    // CHECK: StorageDead([[second_0]]);
+    // CHECK: StorageDead([[second_1]]);
+    // CHECK-NOT: [[array]]
+    let arr = [(x, y), (y, x)];
+    let [(a, b), (c, d)] = arr;
+    (a, b, c, d)
+}
+
+/// Check that an array initialized by repeating an operand is flattened, with the operand copied
+/// into each element.
+pub fn repeat(x: u32) -> u32 {
+    // CHECK-LABEL: fn repeat(
+
+    // CHECK: [[array:_[0-9]+]]: [u32; 2];
+    // CHECK: debug (arr: [u32; 2])[0 of 2] => [[first:_[0-9]+]];
+    // CHECK: debug (arr: [u32; 2])[1 of 2] => [[second:_[0-9]+]];
+
+    // CHECK: bb0: {
+    // CHECK-NOT: [[array]]
+    // CHECK: [[first]] = _1;
+    // CHECK: [[second]] = _1;
+    // CHECK: = [[first]];
+    // CHECK: = [[second]];
+    // CHECK-NOT: [[array]]
+    let arr = [x; 2];
+    let [a, b] = arr;
+    a + b
+}
+
+fn main() {
+    // CHECK-LABEL: fn main(
+    tuples(1, 2);
+    repeat(1);
+}
+
+// EMIT_MIR arrays.tuples.ScalarReplacementOfAggregates.diff
+// EMIT_MIR arrays.repeat.ScalarReplacementOfAggregates.diff
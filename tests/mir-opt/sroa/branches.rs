@@ -0,0 +1,42 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that a struct initialized differently in two branches and read after they join is
+/// flattened: each branch assigns the fragments, the join reads them, and the storage of the
+/// fragments starts before the branches.
+pub fn branches(c: bool, x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn branches(
+    // CHECK: debug ((p: Pair).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((p: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: StorageLive([[a]]);
+    // CHECK: StorageLive([[b]]);
+    // CHECK: switchInt(
+    // CHECK: bb1: {
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: goto -> [[join:bb[0-9]+]];
+    // CHECK: bb2: {
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: goto -> [[join]];
+    // CHECK: [[join]]: {
+    // CHECK: {{_[0-9]+}} = [[a]];
+    // CHECK: {{_[0-9]+}} = [[b]];
+    // CHECK: StorageDead([[a]]);
+    // CHECK: StorageDead([[b]]);
+    let p = if c { Pair { a: x, b: y } } else { Pair { a: y, b: x } };
+    p.a ^ p.b
+}
+
+fn main() {
+    branches(true, 1, 2);
+}
+
+// EMIT_MIR branches.branches.ScalarReplacementOfAggregates.diff
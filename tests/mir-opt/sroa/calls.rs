@@ -0,0 +1,48 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+#[inline(never)]
+fn take(_: u32) {}
+
+#[inline(never)]
+fn take_ref(_: &u32) {}
+
+/// Check that passing a field by value to a call does not make the struct escape, and that the
+/// argument is read from the fragment.
+pub fn by_value(x: u32, y: u32) {
+    // CHECK-LABEL: fn by_value(
+    // CHECK: debug ((s: Pair).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((s: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: [[arg:_[0-9]+]] = [[a]];
+    // CHECK: take(move [[arg]])
+    let s = Pair { a: x, b: y };
+    take(s.a);
+}
+
+/// Check that passing a reference to a field to a call makes the struct escape.
+pub fn by_ref(x: u32, y: u32) {
+    // CHECK-LABEL: fn by_ref(
+    // CHECK: debug s => [[s:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[s]] = Pair {
+    // CHECK: {{_[0-9]+}} = &([[s]].0: u32);
+    let s = Pair { a: x, b: y };
+    take_ref(&s.a);
+}
+
+fn main() {
+    by_value(1, 2);
+    by_ref(1, 2);
+}
+
+// EMIT_MIR calls.by_value.ScalarReplacementOfAggregates.diff
+// EMIT_MIR calls.by_ref.ScalarReplacementOfAggregates.diff
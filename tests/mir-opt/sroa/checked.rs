@@ -0,0 +1,61 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Coverflow-checks=on
+//@ no-prefer-dynamic
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::intrinsics::mir::*;
+
+/// Check that a local only assigned the result of a checked operation is left whole, as
+/// flattening it would only move that result into a temporary.
+pub fn add(a: u32, b: u32) -> u32 {
+    // CHECK-LABEL: fn add(
+    // CHECK: bb0: {
+    // CHECK: [[pair:_[0-9]+]] = AddWithOverflow(
+    // CHECK: assert(!move ([[pair]].1: bool),
+    // CHECK: bb1: {
+    // CHECK: _0 = move ([[pair]].0: u32);
+    a + b
+}
+
+/// Check that the result of a checked operation is computed into a temporary, which is then
+/// split into the value and the overflow flag, when the local is also assigned an aggregate.
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn add_or_zero(a: u32, b: u32, c: bool) -> u32 {
+    // CHECK-LABEL: fn add_or_zero(
+    // CHECK: bb1: {
+    // CHECK: [[temp:_[0-9]+]] = AddWithOverflow(_1, _2);
+    // CHECK: [[value:_[0-9]+]] = ([[temp]].0: u32);
+    // CHECK: [[overflow:_[0-9]+]] = ([[temp]].1: bool);
+    // CHECK: bb2: {
+    // CHECK: [[value]] = const 0_u32;
+    // CHECK: [[overflow]] = const false;
+    // CHECK: bb3: {
+    // CHECK: _0 = [[value]];
+    mir! {
+        let p: (u32, bool);
+        {
+            match c { true => bb1, _ => bb2 }
+        }
+        bb1 = {
+            p = Checked(a + b);
+            Goto(bb3)
+        }
+        bb2 = {
+            p = (0, false);
+            Goto(bb3)
+        }
+        bb3 = {
+            RET = p.0;
+            Return()
+        }
+    }
+}
+
+fn main() {
+    add(5, 6);
+    add_or_zero(5, 6, true);
+}
+
+// EMIT_MIR checked.add.ScalarReplacementOfAggregates.diff
+// EMIT_MIR checked.add_or_zero.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,40 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::intrinsics::mir::*;
+
+/// Check that deinitializing a single field of a flattened tuple deinitializes its fragment.
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn field(a: u32, b: u32) -> u32 {
+    // CHECK-LABEL: fn field(
+
+    // CHECK: [[pair:_[0-9]+]]: (u32, u32);
+    // CHECK: [[first:_[0-9]+]]: u32;
+    // CHECK: [[second:_[0-9]+]]: u32;
+
+    // CHECK: bb0: {
+    // CHECK-NOT: [[pair]]
+    // CHECK: [[first]] = _1;
+    // CHECK: [[second]] = _2;
+    // CHECK: Deinit([[first]]);
+    // CHECK: _0 = [[second]];
+    // CHECK-NOT: [[pair]]
+    mir! {
+        let pair: (u32, u32);
+        {
+            pair = (a, b);
+            Deinit(pair.0);
+            RET = pair.1;
+            Return()
+        }
+    }
+}
+
+fn main() {
+    field(1, 2);
+}
+
+// EMIT_MIR deinit.field.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,63 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+struct Pair {
+    a: u8,
+    b: u32,
+}
+
+enum Single {
+    Only(Pair),
+}
+
+/// Check a field of a struct inside a single-variant enum, which is projected through
+/// `[Downcast(0), Field(0), Field(1)]`. The enum is flattened into its payload, which is then
+/// flattened into its fields.
+pub fn nested_variant(x: u8, y: u32) -> u32 {
+    // CHECK-LABEL: fn nested_variant(
+
+    // CHECK: [[payload:_[0-9]+]]: Pair;
+
+    // CHECK: debug ((((e: Single) as Only).0: Pair).0: u8) => [[a:_[0-9]+]];
+    // CHECK: debug ((((e: Single) as Only).0: Pair).1: u32) => [[b:_[0-9]+]];
+
+    // CHECK: bb0: {
+    // CHECK: [[payload]] = Pair {
+    // CHECK: [[a]] = move ([[payload]].0: u8);
+    // CHECK: [[b]] = move ([[payload]].1: u32);
+    // CHECK: [[read:_[0-9]+]] = [[b]];
+    // CHECK: _0 = [[read]];
+    let e = Single::Only(Pair { a: x, b: y });
+    let Single::Only(Pair { b, .. }) = e;
+    b
+}
+
+enum Wrapper {
+    Both(u8, u32),
+}
+
+/// Check that a single-variant enum is flattened like a struct: the aggregate building the
+/// variant assigns the fragments, and the fields read through the `Downcast` use them.
+pub fn single_variant(x: u8, y: u32) -> (u8, u32) {
+    // CHECK-LABEL: fn single_variant(
+    // CHECK: debug (((w: Wrapper) as Both).0: u8) => [[a:_[0-9]+]];
+    // CHECK: debug (((w: Wrapper) as Both).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: {{_[0-9]+}} = [[a]];
+    // CHECK: {{_[0-9]+}} = [[b]];
+    let w = Wrapper::Both(x, y);
+    let Wrapper::Both(a, b) = w;
+    (a, b)
+}
+
+fn main() {
+    // CHECK-LABEL: fn main(
+    nested_variant(1, 2);
+    single_variant(1, 2);
+}
+
+// EMIT_MIR enums.nested_variant.ScalarReplacementOfAggregates.diff
+// EMIT_MIR enums.single_variant.ScalarReplacementOfAggregates.diff
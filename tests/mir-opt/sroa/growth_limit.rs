@@ -0,0 +1,32 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-local-growth-limit=2
+//@ no-prefer-dynamic
+
+struct Quad {
+    a: u32,
+    b: u32,
+    c: u32,
+    d: u32,
+}
+
+/// Check that SROA stops flattening before the body has more than twice its original locals:
+/// `first` takes the body from 5 to 9 locals, flattening `second` as well would take it to 13.
+pub fn limited() -> u32 {
+    // CHECK-LABEL: fn limited(
+    // CHECK: debug ((first: Quad).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug second => [[second:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[a]] = const 1_u32;
+    // CHECK: [[second]] = Quad {
+    // CHECK: {{_[0-9]+}} = [[a]];
+    // CHECK: {{_[0-9]+}} = ([[second]].3: u32);
+    let first = Quad { a: 1, b: 2, c: 3, d: 4 };
+    let second = Quad { a: 5, b: 6, c: 7, d: 8 };
+    first.a + second.d
+}
+
+fn main() {
+    limited();
+}
+
+// EMIT_MIR growth_limit.limited.ScalarReplacementOfAggregates.diff
@@ -32,10 +32,31 @@ fn foo<T: Err>() {
     }
 }
 
+struct Borrowed<'a> {
+    ptr: &'a u32,
+    count: u32,
+}
+
+// EMIT_MIR lifetimes.borrowed.ScalarReplacementOfAggregates.diff
+fn borrowed<'a>(r: &'a u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn borrowed(
+    // Check that the fragment for the reference field has an erased lifetime, like the
+    // other locals.
+    // CHECK: debug ((s: Borrowed<'_>).0: &u32) => [[ptr:_[0-9]+]];
+    // CHECK: debug ((s: Borrowed<'_>).1: u32) => [[count:_[0-9]+]];
+    // CHECK-NOT: &'a u32
+    // CHECK: [[ptr]] = move {{_[0-9]+}};
+    // CHECK: [[count]] = move {{_[0-9]+}};
+    let s = Borrowed { ptr: r, count: n };
+    let v = *s.ptr;
+    v + s.count
+}
+
 impl Err for () {
     type Err = ();
 }
 
 fn main() {
-    foo::<()>()
+    foo::<()>();
+    borrowed(&5, 6);
 }
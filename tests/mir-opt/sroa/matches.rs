@@ -0,0 +1,37 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that the scrutinee temporary of a by-value `match` on a struct is flattened, and that the
+/// tests and the bindings of the arms read the fragments. The borrows taken by match lowering for
+/// guards are fake borrows, which are gone by the time SROA runs; matching on a reference to the
+/// struct instead takes a real borrow, which keeps it whole.
+pub fn match_struct(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn match_struct(
+    // CHECK: debug a => [[a:_[0-9]+]];
+    // CHECK: debug b => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[frag_a:_[0-9]+]] = move {{_[0-9]+}};
+    // CHECK: [[frag_b:_[0-9]+]] = move {{_[0-9]+}};
+    // CHECK: switchInt([[frag_a]]) -> [0: [[zero:bb[0-9]+]], otherwise: [[other:bb[0-9]+]]];
+    // CHECK: [[other]]: {
+    // CHECK: [[a]] = [[frag_a]];
+    // CHECK: [[b]] = [[frag_b]];
+    // CHECK: [[zero]]: {
+    // CHECK: {{_[0-9]+}} = [[frag_b]];
+    match (Pair { a: x, b: y }) {
+        Pair { a: 0, b } => b,
+        Pair { a, b } => a + b,
+    }
+}
+
+fn main() {
+    match_struct(0, 1);
+}
+
+// EMIT_MIR matches.match_struct.ScalarReplacementOfAggregates.diff
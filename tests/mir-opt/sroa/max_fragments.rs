@@ -0,0 +1,37 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-max-fragments=3
+//@ no-prefer-dynamic
+
+struct Quad {
+    a: u32,
+    b: u32,
+    c: u32,
+    d: u32,
+}
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that SROA leaves whole the aggregates which would be split into more than 3 fragments:
+/// `quad` has 4 fields and is kept, `pair` has 2 and is flattened.
+pub fn capped() -> u32 {
+    // CHECK-LABEL: fn capped(
+    // CHECK: debug quad => [[quad:_[0-9]+]];
+    // CHECK: debug ((pair: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[quad]] = Quad {
+    // CHECK: [[b]] = const 6_u32;
+    // CHECK: {{_[0-9]+}} = ([[quad]].0: u32);
+    // CHECK: {{_[0-9]+}} = [[b]];
+    let quad = Quad { a: 1, b: 2, c: 3, d: 4 };
+    let pair = Pair { a: 5, b: 6 };
+    quad.a + pair.b
+}
+
+fn main() {
+    capped();
+}
+
+// EMIT_MIR max_fragments.capped.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,30 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that an aggregate written with its fields out of order is expanded into assignments to
+/// the fragments in the declared order of the fields, after its operands were evaluated in the
+/// written order.
+pub fn reversed(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn reversed(
+    // CHECK: debug ((p: Pair).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((p: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[y:_[0-9]+]] = _2;
+    // CHECK: [[x:_[0-9]+]] = _1;
+    // CHECK-NEXT: [[a]] = move [[x]];
+    // CHECK-NEXT: [[b]] = move [[y]];
+    let p = Pair { b: y, a: x };
+    p.a
+}
+
+fn main() {
+    reversed(1, 2);
+}
+
+// EMIT_MIR order.reversed.ScalarReplacementOfAggregates.diff
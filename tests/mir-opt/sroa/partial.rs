@@ -0,0 +1,59 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-partial
+//@ no-prefer-dynamic
+
+use std::ptr::addr_of;
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+#[inline(never)]
+fn take_ref(_: &u32) {}
+
+#[inline(never)]
+fn take_ptr(_: *const u32) {}
+
+/// Check that with `-Zmir-sroa-partial`, a struct with a borrowed field keeps that field, and its
+/// other field is flattened.
+pub fn borrowed_field(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn borrowed_field(
+    // CHECK: [[s:_[0-9]+]]: Pair;
+    // CHECK: debug ((s: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: debug ((s: Pair).0: u32) => ([[s]].0: u32);
+    // CHECK: bb0: {
+    // CHECK: StorageLive([[b]]);
+    // CHECK: StorageLive([[s]]);
+    // CHECK: ([[s]].0: u32) = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: {{_[0-9]+}} = &([[s]].0: u32);
+    // CHECK: _0 = [[b]];
+    // CHECK: StorageDead([[b]]);
+    // CHECK: StorageDead([[s]]);
+    let s = Pair { a: x, b: y };
+    take_ref(&s.a);
+    s.b
+}
+
+/// Check that a raw pointer to a field still keeps the whole struct, as it may be used to reach
+/// the other field.
+pub fn raw_pointer(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn raw_pointer(
+    // CHECK: debug s => [[s:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[s]] = Pair {
+    // CHECK: {{_[0-9]+}} = &raw const ([[s]].0: u32);
+    // CHECK: _0 = ([[s]].1: u32);
+    let s = Pair { a: x, b: y };
+    take_ptr(addr_of!(s.a));
+    s.b
+}
+
+fn main() {
+    borrowed_field(1, 2);
+    raw_pointer(1, 2);
+}
+
+// EMIT_MIR partial.borrowed_field.ScalarReplacementOfAggregates.diff
+// EMIT_MIR partial.raw_pointer.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,32 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-preserve=kept
+//@ no-prefer-dynamic
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that the variable named by `-Zmir-sroa-preserve` is kept whole, while the others are
+/// still flattened.
+pub fn preserved(x: u32, y: u32) -> u32 {
+    // CHECK-LABEL: fn preserved(
+    // CHECK: debug kept => [[kept:_[0-9]+]];
+    // CHECK: debug ((split: Pair).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((split: Pair).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[kept]] = Pair {
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: {{_[0-9]+}} = ([[kept]].0: u32);
+    // CHECK: {{_[0-9]+}} = [[b]];
+    let kept = Pair { a: x, b: y };
+    let split = Pair { a: y, b: x };
+    kept.a + split.b
+}
+
+fn main() {
+    preserved(5, 6);
+}
+
+// EMIT_MIR preserve.preserved.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,39 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-emit-retag
+//@ no-prefer-dynamic
+
+struct Pair {
+    r: &'static u32,
+    n: u32,
+}
+
+struct Plain {
+    a: u32,
+    b: u32,
+}
+
+/// Check that a struct which is retagged as a whole is not flattened, while a struct without
+/// references, which is never retagged, still is.
+pub fn retagged(r: &'static u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn retagged(
+    // CHECK: debug p => [[pair:_[0-9]+]];
+    // CHECK: debug ((q: Plain).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((q: Plain).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[pair]] = Pair {
+    // CHECK: Retag([[pair]]);
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: {{_[0-9]+}} = ([[pair]].1: u32);
+    // CHECK: {{_[0-9]+}} = [[b]];
+    let p = Pair { r, n };
+    let q = Plain { a: n, b: n };
+    p.n + q.b
+}
+
+fn main() {
+    // CHECK-LABEL: fn main(
+    retagged(&5, 6);
+}
+
+// EMIT_MIR retag.retagged.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,26 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-return-place
+//@ no-prefer-dynamic
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+/// Check that with `-Zmir-sroa-return-place`, the return place is flattened, and reassembled from
+/// its fragments just before `return`.
+pub fn build(x: u32, y: u32) -> Pair {
+    // CHECK-LABEL: fn build(
+    // CHECK: bb0: {
+    // CHECK: [[a:_[0-9]+]] = move {{_[0-9]+}};
+    // CHECK: [[b:_[0-9]+]] = move {{_[0-9]+}};
+    // CHECK: _0 = Pair { a: move [[a]], b: move [[b]] };
+    // CHECK-NEXT: return;
+    Pair { a: x, b: y }
+}
+
+fn main() {
+    build(1, 2);
+}
+
+// EMIT_MIR return_place.build.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,38 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::intrinsics::mir::*;
+
+pub enum Single {
+    Only(u32),
+}
+
+/// Check that setting the discriminant of a flattened single-variant enum is removed, as the
+/// enum has no discriminant left to set.
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn set_discriminant(x: u32) -> u32 {
+    // CHECK-LABEL: fn set_discriminant(
+    // CHECK: [[payload:_[0-9]+]]: u32;
+    // CHECK: bb0: {
+    // CHECK: [[payload]] = _1;
+    // CHECK-NOT: discriminant
+    // CHECK: _0 = [[payload]];
+    mir! {
+        let e: Single;
+        {
+            place!(Field(Variant(e, 0), 0)) = x;
+            SetDiscriminant(e, 0);
+            RET = Field(Variant(e, 0), 0);
+            Return()
+        }
+    }
+}
+
+fn main() {
+    set_discriminant(1);
+}
+
+// EMIT_MIR set_discriminant.set_discriminant.ScalarReplacementOfAggregates.diff
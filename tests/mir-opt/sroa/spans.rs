@@ -0,0 +1,18 @@
+// skip-filecheck
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-include-spans
+//@ no-prefer-dynamic
+
+// Check that the statements added by SROA keep the span of the statement they expand.
+
+const PAIR: (u32, u8) = (5, 9);
+
+// EMIT_MIR spans.constant.ScalarReplacementOfAggregates.diff
+pub fn constant() -> u32 {
+    let y = PAIR;
+    y.0
+}
+
+fn main() {
+    constant();
+}
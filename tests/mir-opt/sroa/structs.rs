@@ -23,7 +23,7 @@ pub fn dropping() {
     S(Tag(0), Tag(1), Tag(2)).1;
 }
 
-/// Check that SROA excludes enums.
+/// Check that SROA excludes enums with several variants.
 pub fn enums(a: usize) -> usize {
     // CHECK-LABEL: fn enums(
 
@@ -212,6 +212,221 @@ fn constant() {
     let u = y.1;
 }
 
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+struct Wrapper(Foo);
+
+/// Check that copying an aggregate out of a dereferenced transparent wrapper projects the fields
+/// after the deref, keeping the resulting places canonical.
+fn deref_wrapper(x: &Wrapper) {
+    // CHECK-LABEL: fn deref_wrapper(
+
+    // CHECK: [[external:_[0-9]+]]: &Wrapper) ->
+    // CHECK: [[internal:_[0-9]+]]: Foo;
+    // CHECK: [[byte:_[0-9]+]]: u8;
+    // CHECK: [[unit:_[0-9]+]]: ();
+    // CHECK: [[str:_[0-9]+]]: &str;
+    // CHECK: [[opt_isize:_[0-9]+]]: std::option::Option<isize>;
+
+    // CHECK: bb0: {
+    // CHECK: [[byte]] = (((*[[external]]).0: Foo).0: u8);
+    // CHECK: [[unit]] = (((*[[external]]).0: Foo).1: ());
+    // CHECK: [[str]] = (((*[[external]]).0: Foo).2: &str);
+    // CHECK: [[opt_isize]] = (((*[[external]]).0: Foo).3: std::option::Option<isize>);
+
+    let y = x.0;
+    let t = y.a;
+    let u = y.c;
+}
+
+#[repr(C)]
+struct Ffi {
+    a: u8,
+    b: u32,
+}
+
+/// Check that SROA flattens a `#[repr(C)]` struct whose address is never taken. Only locals that
+/// don't escape are flattened, so nothing can observe that the C layout is gone.
+pub fn repr_c(x: u8, y: u32) -> u32 {
+    // CHECK-LABEL: fn repr_c(
+    // CHECK: debug ((s: Ffi).0: u8) => [[a:_[0-9]+]];
+    // CHECK: debug ((s: Ffi).1: u32) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[a]] = move {{_[0-9]+}};
+    // CHECK: [[b]] = move {{_[0-9]+}};
+    // CHECK: {{_[0-9]+}} = [[a]];
+    // CHECK: {{_[0-9]+}} = [[b]];
+    let s = Ffi { a: x, b: y };
+    s.a as u32 + s.b
+}
+
+struct Inner {
+    a: u32,
+    b: u8,
+}
+
+struct Outer {
+    inner: Inner,
+    x: u32,
+}
+
+const INNER: Inner = Inner { a: 5, b: 9 };
+
+/// Check that a constant operand of an aggregate, which is flattened in a later round, is split
+/// into the fragments of the inner fragment.
+pub fn constant_operand() -> u32 {
+    // CHECK-LABEL: fn constant_operand(
+    // CHECK: debug (((s: Outer).0: Inner).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug (((s: Outer).0: Inner).1: u8) => [[b:_[0-9]+]];
+    // CHECK: debug ((s: Outer).1: u32) => [[x:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[inner:_[0-9]+]] = const INNER;
+    // CHECK: [[a]] = move ([[inner]].0: u32);
+    // CHECK: [[b]] = move ([[inner]].1: u8);
+    // CHECK: [[x]] = const 1_u32;
+    // CHECK: {{_[0-9]+}} = [[a]];
+    // CHECK: {{_[0-9]+}} = [[x]];
+    let s = Outer { inner: INNER, x: 1 };
+    s.inner.a + s.x
+}
+
+#[derive(Copy, Clone)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+/// Check that copying an aggregate out of a reference reads each field through the deref, with
+/// the deref first.
+pub fn from_mut(p: &mut Point) -> u32 {
+    // CHECK-LABEL: fn from_mut(
+    // CHECK: debug ((s: Point).0: u32) => [[x:_[0-9]+]];
+    // CHECK: debug ((s: Point).1: u32) => [[y:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[x]] = ((*_1).0: u32);
+    // CHECK: [[y]] = ((*_1).1: u32);
+    let s = *p;
+    s.x + s.y
+}
+
+/// Check that moving an aggregate out of a field of a local which is not flattened moves each
+/// field of that place into the fragments.
+pub fn from_arg(o: Outer) -> u32 {
+    // CHECK-LABEL: fn from_arg(
+    // CHECK: debug ((s: Inner).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug ((s: Inner).1: u8) => [[b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[a]] = move ((_1.0: Inner).0: u32);
+    // CHECK: [[b]] = move ((_1.0: Inner).1: u8);
+    // CHECK: _0 = [[a]];
+    let s = o.inner;
+    s.a
+}
+
+/// Check that a struct nested in a flattened struct is flattened in turn, so that reading a field
+/// of the inner struct reads a leaf local.
+pub fn nested(a: u32, b: u8, x: u32) -> u8 {
+    // CHECK-LABEL: fn nested(
+    // CHECK: debug (((o: Outer).0: Inner).0: u32) => [[a:_[0-9]+]];
+    // CHECK: debug (((o: Outer).0: Inner).1: u8) => [[b:_[0-9]+]];
+    // CHECK: debug ((o: Outer).1: u32) => [[x:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[a]] = move ({{_[0-9]+}}.0: u32);
+    // CHECK: [[b]] = move ({{_[0-9]+}}.1: u8);
+    // CHECK: [[x]] = move {{_[0-9]+}};
+    // CHECK: _0 = [[b]];
+    let o = Outer { inner: Inner { a, b }, x };
+    o.inner.b
+}
+
+struct Moved {
+    a: u8,
+    b: u32,
+}
+
+/// Check that moving a flattened local into another moves each fragment into the matching one.
+pub fn moves(x: u8, y: u32) -> u32 {
+    // CHECK-LABEL: fn moves(
+    // CHECK: debug ((a: Moved).0: u8) => [[a_a:_[0-9]+]];
+    // CHECK: debug ((a: Moved).1: u32) => [[a_b:_[0-9]+]];
+    // CHECK: debug ((b: Moved).0: u8) => [[b_a:_[0-9]+]];
+    // CHECK: debug ((b: Moved).1: u32) => [[b_b:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[b_a]] = move [[a_a]];
+    // CHECK: [[b_b]] = move [[a_b]];
+    // CHECK: _0 = [[b_b]];
+    let a = Moved { a: x, b: y };
+    let b = a;
+    b.b
+}
+
+struct Holder {
+    ptr: &'static u32,
+    count: u32,
+}
+
+/// Check that reading a reference field through `CopyForDeref` does not make the aggregate escape.
+pub fn copy_for_deref(r: &'static u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn copy_for_deref(
+    // CHECK: debug ((s: Holder).0: &u32) => [[ptr:_[0-9]+]];
+    // CHECK: debug ((s: Holder).1: u32) => [[count:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[ptr]] = move {{_[0-9]+}};
+    // CHECK: [[count]] = move {{_[0-9]+}};
+    // CHECK: [[tmp:_[0-9]+]] = deref_copy [[ptr]];
+    // CHECK: {{_[0-9]+}} = (*[[tmp]]);
+    // CHECK: {{_[0-9]+}} = [[count]];
+    let s = Holder { ptr: r, count: n };
+    let v = *s.ptr;
+    v + s.count
+}
+
+/// Check that a box copied out of a reference with `CopyForDeref` is flattened, each fragment
+/// being copied out of the referenced box.
+pub fn deref_box(b: &Box<(u32, u32)>) -> u32 {
+    // CHECK-LABEL: fn deref_box(
+    // CHECK: bb0: {
+    // CHECK-NOT: deref_copy
+    // CHECK: [[ptr:_[0-9]+]] = (((*_1).0: {{.*}}).0: std::ptr::NonNull<(u32, u32)>);
+    // CHECK: {{_[0-9]+}} = ([[ptr]].0: *const (u32, u32));
+    b.0
+}
+
+struct Empty;
+
+/// Check that a local of an empty struct is left untouched: it has no fields to flatten.
+pub fn empty() {
+    // CHECK-LABEL: fn empty(
+    // CHECK: debug u => [[u:_[0-9]+]];
+    // CHECK: debug _moved => [[moved:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK-NOT: nop;
+    // CHECK: [[u]] = Empty;
+    // CHECK-NOT: nop;
+    // CHECK: [[moved]] = move [[u]];
+    // CHECK-NOT: nop;
+    // CHECK: return;
+    let u = Empty;
+    let _moved = u;
+}
+
+/// Check that a flattened local which is only described by debuginfo afterwards is rewritten to
+/// debuginfo fragments, and does not appear in the body any more.
+pub fn debuginfo_only(x: u32, y: u32) {
+    // CHECK-LABEL: fn debuginfo_only(
+    // CHECK: [[p:_[0-9]+]]: Point;
+    // CHECK-NOT: debug _p => [[p]];
+    // CHECK: debug ((_p: Point).0: u32) => [[x:_[0-9]+]];
+    // CHECK: debug ((_p: Point).1: u32) => [[y:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK-NOT: [[p]]
+    // CHECK: [[x]] = move {{_[0-9]+}};
+    // CHECK: [[y]] = move {{_[0-9]+}};
+    // CHECK-NOT: [[p]]
+    // CHECK: return;
+    let _p = Point { x, y };
+}
+
 fn main() {
     // CHECK-LABEL: fn main(
     dropping();
@@ -223,6 +438,17 @@ fn main() {
     copies(Foo { a: 5, b: (), c: "a", d: Some(-4) });
     ref_copies(&Foo { a: 5, b: (), c: "a", d: Some(-4) });
     constant();
+    deref_wrapper(&Wrapper(Foo { a: 5, b: (), c: "a", d: Some(-4) }));
+    copy_for_deref(&5, 6);
+    deref_box(&Box::new((5, 6)));
+    moves(5, 6);
+    repr_c(5, 6);
+    constant_operand();
+    from_mut(&mut Point { x: 1, y: 2 });
+    from_arg(Outer { inner: Inner { a: 1, b: 2 }, x: 3 });
+    empty();
+    nested(1, 2, 3);
+    debuginfo_only(1, 2);
 }
 
 // EMIT_MIR structs.dropping.ScalarReplacementOfAggregates.diff
@@ -234,3 +460,14 @@ fn main() {
 // EMIT_MIR structs.copies.ScalarReplacementOfAggregates.diff
 // EMIT_MIR structs.ref_copies.ScalarReplacementOfAggregates.diff
 // EMIT_MIR structs.constant.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.deref_wrapper.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.copy_for_deref.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.deref_box.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.moves.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.repr_c.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.constant_operand.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.from_mut.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.from_arg.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.empty.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.nested.ScalarReplacementOfAggregates.diff
+// EMIT_MIR structs.debuginfo_only.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,67 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::intrinsics::mir::*;
+
+/// Check that a local only assigned the result of a transmute is left whole, as flattening it
+/// would only move that result into a temporary.
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn from_bits(x: u64) -> u32 {
+    // CHECK-LABEL: fn from_bits(
+    // CHECK: bb0: {
+    // CHECK: [[pair:_[0-9]+]] = _1 as (u32, u32) (Transmute);
+    // CHECK: _0 = ([[pair]].1: u32);
+    mir! {
+        let p: (u32, u32);
+        {
+            p = CastTransmute::<u64, (u32, u32)>(x);
+            RET = p.1;
+            Return()
+        }
+    }
+}
+
+/// Check that a local assigned the result of a transmute is flattened when it is also assigned an
+/// aggregate, its fragments being copied out of a temporary which holds the whole result.
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn from_bits_or_zero(x: u64, c: bool) -> u32 {
+    // CHECK-LABEL: fn from_bits_or_zero(
+    // CHECK: bb1: {
+    // CHECK: [[temp:_[0-9]+]] = _1 as (u32, u32) (Transmute);
+    // CHECK: [[low:_[0-9]+]] = ([[temp]].0: u32);
+    // CHECK: [[high:_[0-9]+]] = ([[temp]].1: u32);
+    // CHECK: bb2: {
+    // CHECK: [[low]] = const 0_u32;
+    // CHECK: [[high]] = const 0_u32;
+    // CHECK: bb3: {
+    // CHECK: _0 = [[high]];
+    mir! {
+        let p: (u32, u32);
+        {
+            match c { true => bb1, _ => bb2 }
+        }
+        bb1 = {
+            p = CastTransmute::<u64, (u32, u32)>(x);
+            Goto(bb3)
+        }
+        bb2 = {
+            p = (0, 0);
+            Goto(bb3)
+        }
+        bb3 = {
+            RET = p.1;
+            Return()
+        }
+    }
+}
+
+fn main() {
+    from_bits(1);
+    from_bits_or_zero(1, true);
+}
+
+// EMIT_MIR transmute.from_bits.ScalarReplacementOfAggregates.diff
+// EMIT_MIR transmute.from_bits_or_zero.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,25 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+#[repr(transparent)]
+struct W(u64);
+
+/// Check that a `repr(transparent)` newtype is replaced by a single local of its field type, and
+/// that no copy of the whole wrapper is left behind.
+pub fn wrap(x: u64) -> u64 {
+    // CHECK-LABEL: fn wrap(
+    // CHECK: debug ((w: W).0: u64) => [[inner:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK-NOT: W(
+    // CHECK: [[inner]] = move {{_[0-9]+}};
+    // CHECK: _0 = [[inner]];
+    let w = W(x);
+    w.0
+}
+
+fn main() {
+    wrap(5);
+}
+
+// EMIT_MIR transparent.wrap.ScalarReplacementOfAggregates.diff
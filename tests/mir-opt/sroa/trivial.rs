@@ -0,0 +1,50 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort -Zmir-sroa-skip-trivial
+//@ no-prefer-dynamic
+
+pub struct Meters(u32);
+
+/// Check that a single-field struct which is only moved whole is not flattened.
+pub fn moved_once(x: u32) -> Meters {
+    // CHECK-LABEL: fn moved_once(
+    // CHECK: debug m => [[m:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[m]] = Meters(
+    // CHECK: _0 = move [[m]];
+    let m = Meters(x);
+    m
+}
+
+/// Check that a single-field struct whose field is read once is still flattened, as the read is
+/// not a move of the whole struct.
+pub fn read_once(x: u32) -> u32 {
+    // CHECK-LABEL: fn read_once(
+    // CHECK: debug ((m: Meters).0: u32) => [[field:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[field]] = move {{_[0-9]+}};
+    // CHECK: _0 = [[field]];
+    let m = Meters(x);
+    m.0
+}
+
+/// Check that a single-field struct whose field is read several times is still flattened.
+pub fn read_twice(x: u32) -> u32 {
+    // CHECK-LABEL: fn read_twice(
+    // CHECK: debug ((m: Meters).0: u32) => [[field:_[0-9]+]];
+    // CHECK: bb0: {
+    // CHECK: [[field]] = move {{_[0-9]+}};
+    // CHECK: {{_[0-9]+}} = [[field]];
+    // CHECK: {{_[0-9]+}} = [[field]];
+    let m = Meters(x);
+    m.0 + m.0
+}
+
+fn main() {
+    moved_once(5);
+    read_once(5);
+    read_twice(5);
+}
+
+// EMIT_MIR trivial.moved_once.ScalarReplacementOfAggregates.diff
+// EMIT_MIR trivial.read_once.ScalarReplacementOfAggregates.diff
+// EMIT_MIR trivial.read_twice.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,102 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+struct Wide {
+    f0: u32,
+    f1: u32,
+    f2: u32,
+    f3: u32,
+    f4: u32,
+    f5: u32,
+    f6: u32,
+    f7: u32,
+    f8: u32,
+    f9: u32,
+    f10: u32,
+    f11: u32,
+    f12: u32,
+    f13: u32,
+    f14: u32,
+    f15: u32,
+    f16: u32,
+    f17: u32,
+    f18: u32,
+    f19: u32,
+    f20: u32,
+    f21: u32,
+    f22: u32,
+    f23: u32,
+    f24: u32,
+    f25: u32,
+    f26: u32,
+    f27: u32,
+    f28: u32,
+    f29: u32,
+    f30: u32,
+    f31: u32,
+    f32: u32,
+    f33: u32,
+    f34: u32,
+    f35: u32,
+    f36: u32,
+    f37: u32,
+    f38: u32,
+    f39: u32,
+    f40: u32,
+    f41: u32,
+    f42: u32,
+    f43: u32,
+    f44: u32,
+    f45: u32,
+    f46: u32,
+    f47: u32,
+    f48: u32,
+    f49: u32,
+    f50: u32,
+    f51: u32,
+    f52: u32,
+    f53: u32,
+    f54: u32,
+    f55: u32,
+    f56: u32,
+    f57: u32,
+    f58: u32,
+    f59: u32,
+    f60: u32,
+    f61: u32,
+    f62: u32,
+    f63: u32,
+}
+
+/// Check the expansion of a struct with many fields: each field gets a fragment, and the
+/// aggregate, the storage statements and the debuginfo are expanded into one entry per fragment.
+/// MIR building emits no `Deinit`, so that expansion is not covered here.
+pub fn wide(x: u32) -> u32 {
+    // CHECK-LABEL: fn wide(
+    // CHECK-COUNT-64: let _{{[0-9]+}}: u32;
+    // CHECK-COUNT-64: debug ((w: Wide).{{[0-9]+}}: u32) => _{{[0-9]+}};
+    // CHECK: bb0: {
+    // CHECK-COUNT-64: StorageLive(_{{[0-9]+}});
+    // CHECK: [[f0:_[0-9]+]] = move {{_[0-9]+}};
+    // CHECK-COUNT-63: _{{[0-9]+}} = const {{[0-9]+}}_u32;
+    // CHECK: {{_[0-9]+}} = [[f0]];
+    // CHECK-COUNT-64: StorageDead(_{{[0-9]+}});
+    // CHECK: return;
+    let w = Wide {
+        f0: x, f1: 1, f2: 2, f3: 3, f4: 4, f5: 5, f6: 6, f7: 7, f8: 8, f9: 9, f10: 10, f11: 11,
+        f12: 12, f13: 13, f14: 14, f15: 15, f16: 16, f17: 17, f18: 18, f19: 19, f20: 20, f21: 21,
+        f22: 22, f23: 23, f24: 24, f25: 25, f26: 26, f27: 27, f28: 28, f29: 29, f30: 30, f31: 31,
+        f32: 32, f33: 33, f34: 34, f35: 35, f36: 36, f37: 37, f38: 38, f39: 39, f40: 40, f41: 41,
+        f42: 42, f43: 43, f44: 44, f45: 45, f46: 46, f47: 47, f48: 48, f49: 49, f50: 50, f51: 51,
+        f52: 52, f53: 53, f54: 54, f55: 55, f56: 56, f57: 57, f58: 58, f59: 59, f60: 60, f61: 61,
+        f62: 62, f63: 63,
+    };
+    w.f0 + w.f63
+}
+
+fn main() {
+    wide(5);
+}
+
+// EMIT_MIR wide.wide.ScalarReplacementOfAggregates.diff
@@ -0,0 +1,34 @@
+#![feature(custom_mir, core_intrinsics)]
+
+extern crate core;
+use core::intrinsics::mir::*;
+
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn make() -> (u32, u32) {
+    (1, 2)
+}
+
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn reasons(a: (u32, u32)) -> u32 {
+    mir! {
+        let pair: (u32, u32);
+        let borrowed: (u32, u32);
+        let reference: &(u32, u32);
+        let called: (u32, u32);
+        let bits: Bits;
+        {
+            pair = (1, 2);
+            borrowed = a;
+            reference = &borrowed;
+            Call(called = make(), ReturnTo(bb1), UnwindContinue())
+        }
+        bb1 = {
+            RET = pair.0;
+            Return()
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Checks that `-Zmir-sroa-escape-reasons` emits a note saying why scalar replacement of aggregates
+// leaves each local of a body whole, with the location of the use which made it escape, and leaves
+// out the locals which are flattened.
+
+use run_make_support::{rustc, tmp_dir};
+
+fn main() {
+    let output = rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .output(tmp_dir().join("liblib.rlib"))
+        .arg("-Zmir-opt-level=0")
+        .arg("-Zmir-enable-passes=+ScalarReplacementOfAggregates")
+        .arg("-Zmir-sroa-escape-reasons")
+        .run();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let note = |local: &str, reason: &str| {
+        format!("note: SROA leaves `{local}` of `reasons` whole: {reason}\n")
+    };
+    // The return place and the argument.
+    assert!(stderr.contains(&note("_0", "it is the return place or an argument")), "{stderr}");
+    assert!(stderr.contains(&note("_1", "it is the return place or an argument")), "{stderr}");
+    // `pair` is flattened.
+    assert!(!stderr.contains("`_2` of `reasons`"), "{stderr}");
+    // `borrowed`, whose address is taken by the third statement.
+    assert!(stderr.contains(&note("_3", "its address is taken at bb0[2]")), "{stderr}");
+    // `called`, assigned by the call terminating the first block.
+    let call = note("_5", "it is the destination of the call at bb0[3]");
+    assert!(stderr.contains(&call), "{stderr}");
+    // `bits`.
+    assert!(stderr.contains(&note("_6", "it is a union")), "{stderr}");
+}
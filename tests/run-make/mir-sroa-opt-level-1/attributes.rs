@@ -0,0 +1,23 @@
+#![feature(rustc_attrs)]
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+#[rustc_mir_sroa]
+pub fn forced(x: u32, y: u32) -> u32 {
+    let pair = Pair { a: x, b: y };
+    pair.a + pair.b
+}
+
+#[rustc_no_mir_sroa]
+pub fn prevented(x: u32, y: u32) -> u32 {
+    let pair = Pair { a: x, b: y };
+    pair.a + pair.b
+}
+
+pub fn unmarked(x: u32, y: u32) -> u32 {
+    let pair = Pair { a: x, b: y };
+    pair.a + pair.b
+}
@@ -0,0 +1,9 @@
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+pub fn sum(x: u32, y: u32) -> u32 {
+    let pair = Pair { a: x, b: y };
+    pair.a + pair.b
+}
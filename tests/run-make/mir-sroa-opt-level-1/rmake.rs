@@ -0,0 +1,69 @@
+// Checks that scalar replacement of aggregates only runs at `-Zmir-opt-level=1` when it is
+// explicitly enabled, with `-Zmir-sroa=yes` or with
+// `-Zmir-enable-passes=+ScalarReplacementOfAggregates`, and that the latter takes precedence.
+// `#[rustc_mir_sroa]` and `#[rustc_no_mir_sroa]` take precedence over both, for their function.
+
+use run_make_support::{rustc, tmp_dir};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn ran_sroa(dump_dir: &Path) -> bool {
+    ran_sroa_on(dump_dir, "")
+}
+
+/// Whether SROA ran on the functions whose name contains `function`.
+fn ran_sroa_on(dump_dir: &Path, function: &str) -> bool {
+    fs::read_dir(dump_dir).is_ok_and(|mut entries| {
+        entries.any(|entry| {
+            let file_name = entry.unwrap().file_name().into_string().unwrap();
+            file_name.contains(function) && file_name.contains("ScalarReplacementOfAggregates")
+        })
+    })
+}
+
+/// Compiles `lib.rs` at `-Zmir-opt-level=1`, returning the directory the SROA MIR is dumped to.
+fn compile(name: &str, extra_args: &[&str]) -> PathBuf {
+    compile_input("lib.rs", name, extra_args)
+}
+
+fn compile_input(input: &str, name: &str, extra_args: &[&str]) -> PathBuf {
+    let dump_dir = tmp_dir().join(name);
+    rustc()
+        .input(input)
+        .crate_type("lib")
+        .output(tmp_dir().join(format!("lib{name}.rlib")))
+        .arg("-Zmir-opt-level=1")
+        .arg("-Zdump-mir=ScalarReplacementOfAggregates")
+        .arg(format!("-Zdump-mir-dir={}", dump_dir.display()))
+        .args(extra_args)
+        .run();
+    dump_dir
+}
+
+fn main() {
+    assert!(!ran_sroa(&compile("default", &[])));
+    assert!(ran_sroa(&compile("enabled", &["-Zmir-sroa=yes"])));
+    assert!(!ran_sroa(&compile("disabled", &["-Zmir-sroa=no"])));
+    assert!(ran_sroa(&compile(
+        "pass-enabled",
+        &["-Zmir-enable-passes=+ScalarReplacementOfAggregates"]
+    )));
+    assert!(!ran_sroa(&compile(
+        "pass-disabled",
+        &["-Zmir-sroa=yes", "-Zmir-enable-passes=-ScalarReplacementOfAggregates"],
+    )));
+
+    let dump_dir = compile_input("attributes.rs", "attributes", &[]);
+    assert!(ran_sroa_on(&dump_dir, ".forced."));
+    assert!(!ran_sroa_on(&dump_dir, ".prevented."));
+    assert!(!ran_sroa_on(&dump_dir, ".unmarked."));
+
+    let dump_dir = compile_input(
+        "attributes.rs",
+        "attributes-enabled",
+        &["-Zmir-enable-passes=+ScalarReplacementOfAggregates"],
+    );
+    assert!(ran_sroa_on(&dump_dir, ".forced."));
+    assert!(!ran_sroa_on(&dump_dir, ".prevented."));
+    assert!(ran_sroa_on(&dump_dir, ".unmarked."));
+}
@@ -0,0 +1,36 @@
+#![feature(custom_mir, core_intrinsics)]
+
+extern crate core;
+use core::intrinsics::mir::*;
+
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+pub fn flattened(x: u32, y: u32) -> u32 {
+    let p = Pair { a: x, b: y };
+    p.a ^ p.b
+}
+
+#[custom_mir(dialect = "runtime", phase = "initial")]
+pub fn checked(a: u32, b: u32, c: bool) -> u32 {
+    mir! {
+        let p: (u32, bool);
+        {
+            match c { true => bb1, _ => bb2 }
+        }
+        bb1 = {
+            p = Checked(a + b);
+            Goto(bb3)
+        }
+        bb2 = {
+            p = (0, false);
+            Goto(bb3)
+        }
+        bb3 = {
+            RET = p.0;
+            Return()
+        }
+    }
+}
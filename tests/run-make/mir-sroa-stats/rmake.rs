@@ -0,0 +1,27 @@
+// Checks that `-Zmir-sroa-stats` prints how many locals scalar replacement of aggregates split
+// in each body, without counting the temporaries holding whole checked results as fragments.
+
+use run_make_support::{rustc, tmp_dir};
+
+fn main() {
+    let output = rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .output(tmp_dir().join("liblib.rlib"))
+        .arg("-Zmir-opt-level=2")
+        .arg("-Zmir-sroa-stats")
+        .run();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let stats_of = |name: &str| {
+        stderr
+            .split("sroa-stats SROA STATS for `")
+            .find(|stats| stats.strip_prefix(name).is_some_and(|rest| rest.starts_with('`')))
+            .unwrap_or_else(|| panic!("no stats for `{name}` in:\n{stderr}"))
+    };
+    for name in ["flattened", "checked"] {
+        let stats = stats_of(name);
+        assert!(stats.contains("sroa-stats Replaced locals                 1"), "{stats}");
+        assert!(stats.contains("sroa-stats Fragments                       2"), "{stats}");
+        assert!(stats.contains("sroa-stats Expanded aggregates             1"), "{stats}");
+    }
+}
@@ -0,0 +1,26 @@
+// Checks that `--markdown-back-to-top` links each heading of a rendered Markdown file to the
+// title, whose ID doesn't collide with the ones of the headings, and that there are no such links
+// otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("back-to-top", &["--markdown-back-to-top"]);
+    assert!(html.contains(r#"<h1 class="title" id="top">The Title</h1>"#));
+    assert!(html.contains(r#"<h1 id="top-1">"#));
+    assert!(html.contains(r#"<h2 id="details">"#));
+    let link = r##"<a class="back-to-top" href="#top" title="Back to top">↑</a>"##;
+    assert_eq!(html.matches(link).count(), 2);
+
+    let html = render("default", &[]);
+    assert!(html.contains(r#"<h1 class="title">The Title</h1>"#));
+    assert!(html.contains(r#"<h1 id="top">"#));
+    assert!(!html.contains("back-to-top"));
+}
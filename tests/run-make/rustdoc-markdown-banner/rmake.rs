@@ -0,0 +1,47 @@
+// Checks that the banner of a rendered Markdown file is shown above its content, taken from the
+// `banner` metadata or `--markdown-banner`, and escaped unless it comes from `--markdown-banner`
+// and `--markdown-banner-html` is passed.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn banner_and_content(html: &str, banner: &str) -> (usize, usize) {
+    (html.find(banner).unwrap(), html.find("<p>Some text.</p>").unwrap())
+}
+
+fn main() {
+    let metadata_dir = tmp_dir().join("metadata");
+    rustdoc().input("input.md").output(&metadata_dir).run();
+    let html = fs::read_to_string(metadata_dir.join("input.html")).unwrap();
+    let (banner, content) =
+        banner_and_content(&html, r#"<div class="banner">This crate is &lt;deprecated&gt;.</div>"#);
+    assert!(banner < content);
+
+    let metadata_html_dir = tmp_dir().join("metadata-html");
+    rustdoc()
+        .input("input.md")
+        .output(&metadata_html_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-banner-html")
+        .run();
+    let html = fs::read_to_string(metadata_html_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<div class="banner">This crate is &lt;deprecated&gt;.</div>"#));
+    assert!(!html.contains("<deprecated>"));
+
+    let option_dir = tmp_dir().join("option");
+    rustdoc()
+        .input("input.md")
+        .output(&option_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-banner")
+        .arg("Use <a href=\"https://example.com\">the new crate</a>.")
+        .arg("--markdown-banner-html")
+        .run();
+    let html = fs::read_to_string(option_dir.join("input.html")).unwrap();
+    let (banner, content) = banner_and_content(
+        &html,
+        r#"<div class="banner">Use <a href="https://example.com">the new crate</a>.</div>"#,
+    );
+    assert!(banner < content);
+    assert!(!html.contains("deprecated"));
+}
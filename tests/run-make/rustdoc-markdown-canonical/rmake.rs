@@ -0,0 +1,21 @@
+// Checks that a `canonical` metadata line in a rendered Markdown file emits a canonical `<link>`,
+// and that none is emitted otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("canonical");
+    rustdoc().input("input.md").output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(
+        r#"<link rel="canonical" href="https://example.com/docs/page.html?a=1&amp;b=2">"#
+    ));
+
+    let no_canonical = tmp_dir().join("no-canonical.md");
+    fs::write(&no_canonical, "% The Title\n\nSome text.\n").unwrap();
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input(&no_canonical).output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("no-canonical.html")).unwrap();
+    assert!(!html.contains(r#"rel="canonical""#));
+}
@@ -0,0 +1,11 @@
+// Checks that the custom classes of a code block in a rendered Markdown file are added to it.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("code-classes");
+    rustdoc().input("input.md").output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<pre class="language-text my-class"><code>plain"#), "{html}");
+}
@@ -0,0 +1,49 @@
+// Checks that `--markdown-combine` renders several Markdown files into a single page, titled like
+// the first file or by `--markdown-combine-title`, with a table of contents listing every file.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("combined");
+    rustdoc()
+        .input("install.md")
+        .arg("reference.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-combine")
+        .run();
+    let html = fs::read_to_string(out_dir.join("install.html")).unwrap();
+    assert!(!out_dir.join("reference.html").exists());
+    assert!(html.contains("<title>Getting Started</title>"), "{html}");
+    assert!(html.contains("<p>Run the installer.</p>"), "{html}");
+    assert!(html.contains("<p>See the options.</p>"), "{html}");
+    assert!(html.contains(r##"<a href="#getting-started">1 Getting Started</a>"##), "{html}");
+    assert!(html.contains(r##"<a href="#install">1.1 Install</a>"##), "{html}");
+    assert!(html.contains(r##"<a href="#reference">2 Reference</a>"##), "{html}");
+    assert!(html.contains(r##"<a href="#options">2.1 Options</a>"##), "{html}");
+
+    let out_dir = tmp_dir().join("titled");
+    rustdoc()
+        .input("install.md")
+        .arg("reference.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-combine")
+        .arg("--markdown-combine-title")
+        .arg("Manual")
+        .run();
+    let html = fs::read_to_string(out_dir.join("install.html")).unwrap();
+    assert!(html.contains("<title>Manual</title>"), "{html}");
+
+    let output = rustdoc()
+        .input("install.md")
+        .output(tmp_dir().join("title-only"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-combine-title")
+        .arg("Manual")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    let expected = "`--markdown-combine-title` requires `--markdown-combine`";
+    assert!(stderr.contains(expected), "{stderr}");
+}
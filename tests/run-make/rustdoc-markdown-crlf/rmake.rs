@@ -0,0 +1,22 @@
+// Checks that `--markdown-crlf` writes a rendered Markdown file with CRLF line endings, and that
+// it is written with LF line endings otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("crlf", &["--markdown-crlf"]);
+    assert!(html.starts_with("<!DOCTYPE html>\r\n<html lang=\"en\">\r\n"));
+    assert!(html.contains("first line\r\nsecond line"));
+    assert!(!html.replace("\r\n", "").contains('\n'));
+
+    let html = render("lf", &[]);
+    assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n"));
+    assert!(!html.contains('\r'));
+}
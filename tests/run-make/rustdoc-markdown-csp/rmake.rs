@@ -0,0 +1,27 @@
+// Checks that `--markdown-csp` adds a Content Security Policy forbidding inline scripts to a
+// rendered Markdown file, that `--markdown-csp-policy` replaces it, and that none is added
+// otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("default", &["--markdown-csp"]);
+    assert!(html.contains(
+        r#"<meta http-equiv="Content-Security-Policy" content="script-src &#39;self&#39;; object-src &#39;none&#39;; base-uri &#39;self&#39;">"#
+    ));
+
+    let html = render("policy", &["--markdown-csp-policy", "default-src 'none'; img-src https:"]);
+    assert!(html.contains(
+        r#"<meta http-equiv="Content-Security-Policy" content="default-src &#39;none&#39;; img-src https:">"#
+    ));
+
+    let html = render("none", &[]);
+    assert!(!html.contains("Content-Security-Policy"));
+}
@@ -0,0 +1,36 @@
+// Checks that a rendered Markdown file without a `description` metadata line gets its description
+// from the plain text of its first paragraph, truncated to 160 characters, and that an explicit
+// `description` takes precedence.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn description(name: &str, markdown: Option<&str>) -> String {
+    let input = match markdown {
+        Some(markdown) => {
+            let input = tmp_dir().join(format!("{name}.md"));
+            fs::write(&input, markdown).unwrap();
+            input
+        }
+        None => "input.md".into(),
+    };
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input(&input).output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join(input.with_extension("html").file_name().unwrap()))
+        .unwrap();
+    assert_eq!(html.matches(r#"<meta name="description""#).count(), 1);
+    let start = html.find(r#"<meta name="description" content=""#).unwrap() + 34;
+    html[start..start + html[start..].find('"').unwrap()].to_owned()
+}
+
+fn main() {
+    assert_eq!(description("input", None), "The first paragraph, with code &amp; a link.");
+
+    let long = format!("% The Title\n\n{}\n", "word ".repeat(50));
+    let summary = description("long", Some(&long));
+    assert!(summary.ends_with("word…"));
+    assert!(summary.chars().count() <= 160);
+
+    let explicit = "% The Title\n% description: Explicit\n\nSome text.\n";
+    assert_eq!(description("explicit", Some(explicit)), "Explicit");
+}
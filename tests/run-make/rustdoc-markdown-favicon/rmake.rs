@@ -0,0 +1,26 @@
+// Checks that `--markdown-favicon` adds an icon link to a rendered Markdown file, that it
+// overrides a `favicon` metadata line, and that no icon link is added otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, input: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input(input).output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join(input).with_extension("html")).unwrap()
+}
+
+fn main() {
+    let html = render("flag", "plain.md", &["--markdown-favicon", "icons/logo.png?v=1&x=2"]);
+    assert!(html.contains(r#"<link rel="icon" href="icons/logo.png?v=1&amp;x=2">"#));
+
+    let html = render("metadata", "input.md", &[]);
+    assert!(html.contains(r#"<link rel="icon" href="meta.png">"#));
+
+    let html = render("override", "input.md", &["--markdown-favicon", "flag.png"]);
+    assert!(html.contains(r#"<link rel="icon" href="flag.png">"#));
+    assert!(!html.contains("meta.png"));
+
+    let html = render("none", "plain.md", &[]);
+    assert!(!html.contains(r#"rel="icon""#));
+}
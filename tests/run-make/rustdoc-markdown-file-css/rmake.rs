@@ -0,0 +1,25 @@
+// Checks that the `css` metadata line of a rendered Markdown file links its stylesheets after
+// the `--markdown-css` ones, so that they can override them, and that unknown metadata lines
+// are ignored.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("css");
+    rustdoc().input("input.md").arg("--markdown-css").arg("global.css").output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    let global = html.find(r#"<link rel="stylesheet" href="global.css">"#).unwrap();
+    let custom = html.find(r#"<link rel="stylesheet" href="custom.css">"#).unwrap();
+    let print = html.find(r#"<link rel="stylesheet" href="print.css">"#).unwrap();
+    assert!(global < custom && custom < print);
+    assert!(!html.contains("ignored"));
+
+    let no_css = tmp_dir().join("no-css.md");
+    fs::write(&no_css, "% The Title\n\nSome text.\n").unwrap();
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input(&no_css).arg("--markdown-css").arg("global.css").output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("no-css.html")).unwrap();
+    assert!(html.contains(r#"<link rel="stylesheet" href="global.css">"#));
+    assert!(!html.contains("custom.css"));
+}
@@ -0,0 +1,44 @@
+//@ ignore-windows
+// Checks that `--markdown-filter` pipes a rendered Markdown file through the given program, and
+// that a failing filter is reported without leaving an output file behind.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let cat_dir = tmp_dir().join("cat");
+    rustdoc()
+        .input("input.md")
+        .output(&cat_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-filter")
+        .arg("cat")
+        .run();
+    let html = fs::read_to_string(cat_dir.join("input.html")).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<p>Some text.</p>"));
+
+    let sed_dir = tmp_dir().join("sed");
+    rustdoc()
+        .input("input.md")
+        .output(&sed_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-filter")
+        .arg("sed")
+        .arg("--markdown-filter-arg")
+        .arg("s/Some text/Filtered text/")
+        .run();
+    let html = fs::read_to_string(sed_dir.join("input.html")).unwrap();
+    assert!(html.contains("<p>Filtered text.</p>"));
+
+    let output = rustdoc()
+        .input("input.md")
+        .output(tmp_dir().join("false"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-filter")
+        .arg("false")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("markdown filter `false` failed"));
+    assert!(!tmp_dir().join("false").join("input.html").exists());
+}
@@ -0,0 +1,33 @@
+// Checks that a YAML frontmatter block at the start of a rendered Markdown file provides its title
+// and is not rendered, taking precedence over `%` lines, that the `%` lines after a frontmatter
+// title are all metadata, and that a file whose frontmatter has no title and isn't followed by a
+// title line is still rejected.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("frontmatter");
+    rustdoc().input("input.md").output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains("<title>The Title</title>"));
+    assert!(html.contains(r#"<h1 class="title">The Title</h1>"#));
+    assert!(html.contains("Some text."));
+    assert!(!html.contains("Another Title"));
+    assert!(!html.contains("author:"));
+
+    let both = tmp_dir().join("both.md");
+    fs::write(&both, "---\ntitle: The Title\n---\n% status: draft\n\nSome text.\n").unwrap();
+    let output = rustdoc().input(&both).output(tmp_dir().join("both")).run();
+    let html = fs::read_to_string(tmp_dir().join("both/both.html")).unwrap();
+    assert!(html.contains("<title>The Title</title>"));
+    assert!(html.contains(r#"<span class="status status-draft">draft</span>"#));
+    assert!(!html.contains("status:"));
+    assert!(!std::str::from_utf8(&output.stderr).unwrap().contains("unknown metadata key"));
+
+    let untitled = tmp_dir().join("untitled.md");
+    fs::write(&untitled, "---\nauthor: Jane Doe\n---\n\nSome text.\n").unwrap();
+    let output = rustdoc().input(&untitled).output(tmp_dir().join("untitled")).run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("invalid markdown file: no initial lines starting with `# ` or `%`"));
+}
@@ -0,0 +1,39 @@
+// Checks that `--markdown-heading-offset` shifts the level of the headings of a rendered Markdown
+// file, with or without a table of contents, and that the headings start at `<h1>` otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("offset", &["--markdown-heading-offset", "2"]);
+    assert!(html.contains(r#"<h3 id="usage">"#));
+    assert!(html.contains(r#"<h4 id="details">"#));
+    assert!(html.contains(r##"<a href="#usage">1 Usage</a>"##));
+    // The title of the page is not a heading of the file.
+    assert!(html.contains(r#"<h1 class="title">The Title</h1>"#));
+
+    let html = render("no-toc", &["--markdown-heading-offset", "1", "--markdown-no-toc"]);
+    assert!(html.contains(r#"<h2 id="usage">"#));
+    assert!(html.contains(r#"<h3 id="details">"#));
+
+    let html = render("default", &[]);
+    assert!(html.contains(r#"<h1 id="usage">"#));
+    assert!(html.contains(r#"<h2 id="details">"#));
+
+    let out_dir = tmp_dir().join("invalid");
+    let output = rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-heading-offset")
+        .arg("6")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("must be between 0 and 5, got `6`"), "{stderr}");
+}
@@ -0,0 +1,35 @@
+// Checks that `--markdown-highlight-theme` adds the highlighting colors of a bundled theme to a
+// rendered Markdown file, that there are none otherwise, and that unknown themes are rejected.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("ayu", &["--markdown-highlight-theme", "ayu"]);
+    assert!(html.contains("--code-highlight-kw-color:#ff7733;"), "{html}");
+    assert!(html.contains("pre.rust .kw {"));
+    assert!(html.contains(r#"<span class="kw">fn</span>"#));
+
+    let html = render("light", &["--markdown-highlight-theme", "light"]);
+    assert!(html.contains("--code-highlight-kw-color:#8959a8;"), "{html}");
+
+    let html = render("default", &[]);
+    assert!(!html.contains("--code-highlight-"));
+
+    let out_dir = tmp_dir().join("invalid");
+    let output = rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-highlight-theme")
+        .arg("solarized")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("must be one of `light`, `dark`, `ayu`, got `solarized`"), "{stderr}");
+}
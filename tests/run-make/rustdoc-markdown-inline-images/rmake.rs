@@ -0,0 +1,47 @@
+// Checks that `--markdown-inline-images` embeds the local images of a rendered Markdown file as
+// `data:` URIs, leaves remote images alone, and fails on missing or too large images.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let plain_dir = tmp_dir().join("plain");
+    rustdoc().input("input.md").output(&plain_dir).run();
+    let html = fs::read_to_string(plain_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<img src="logo.svg" alt="logo" />"#));
+
+    let inline_dir = tmp_dir().join("inline");
+    rustdoc()
+        .input("input.md")
+        .output(&inline_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-inline-images")
+        .run();
+    let html = fs::read_to_string(inline_dir.join("input.html")).unwrap();
+    assert!(html.contains(
+        r#"<img src="data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHdpZHRoPSIxIiBoZWlnaHQ9IjEiLz4K" alt="logo" />"#
+    ));
+    assert!(html.contains(r#"<img src="https://example.com/remote.png" alt="remote" />"#));
+
+    let output = rustdoc()
+        .input("input.md")
+        .output(tmp_dir().join("limit"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-inline-images")
+        .arg("--markdown-inline-image-limit")
+        .arg("16")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("larger than the limit of 16 bytes for inlined images"));
+
+    let missing = tmp_dir().join("missing.md");
+    fs::write(&missing, "# Missing\n\n![gone](gone.png)\n").unwrap();
+    let output = rustdoc()
+        .input(&missing)
+        .output(tmp_dir().join("missing"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-inline-images")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("gone.png"));
+}
@@ -0,0 +1,27 @@
+// Checks that `--markdown-json-ld` emits a JSON-LD `Article` block built from the metadata of a
+// rendered Markdown file, omitting the fields which aren't provided.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("json-ld");
+    rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-json-ld")
+        .run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<script type="application/ld+json">"#));
+    assert!(html.contains(r#""@type":"Article""#));
+    assert!(html.contains(r#""headline":"The Title""#));
+    assert!(html.contains(r#""author":{"@type":"Person","name":"Jane Doe"}"#));
+    assert!(!html.contains("datePublished"));
+    assert!(!html.contains(r#""description":"#));
+
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input("input.md").output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("input.html")).unwrap();
+    assert!(!html.contains("application/ld+json"));
+}
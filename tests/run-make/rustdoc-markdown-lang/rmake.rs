@@ -0,0 +1,34 @@
+// Checks that `--markdown-lang` sets the language of a rendered Markdown file, and that it is
+// English otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("lang");
+    rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-lang")
+        .arg("fr\"><script>")
+        .run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<html lang="fr&quot;&gt;&lt;script&gt;">"#));
+
+    let out_dir = tmp_dir().join("fr");
+    rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-lang")
+        .arg("fr")
+        .run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<html lang="fr">"#));
+
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input("input.md").output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<html lang="en">"#));
+}
@@ -0,0 +1,36 @@
+// Checks that links between Markdown files rendered together point to the `.html` files, that
+// external links and links to files which aren't rendered are left alone, that links to an
+// anchor which the target file doesn't have are reported, and that the links of a file rendered
+// on its own are left alone.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("links");
+    let output = rustdoc()
+        .input("first.md")
+        .arg("second.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .run();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("first.md:4: `second.md#missing` links to an anchor"), "{stderr}");
+    assert!(stderr.contains("first.md:8: `./second.md#gone` links to an anchor"), "{stderr}");
+    assert!(!stderr.contains("second.md#details"), "{stderr}");
+
+    let first = fs::read_to_string(out_dir.join("first.html")).unwrap();
+    assert!(first.contains(r#"<a href="second.html">"#));
+    assert!(first.contains(r#"<a href="second.html#details">"#));
+    assert!(first.contains(r#"<a href="https://example.com/README.md">"#));
+    assert!(first.contains(r#"<a href="./second.html#gone">"#));
+    assert!(first.contains(r#"<a href="draft.md">"#));
+    let second = fs::read_to_string(out_dir.join("second.html")).unwrap();
+    assert!(second.contains(r#"<a href="first.html">"#));
+
+    let single_dir = tmp_dir().join("single");
+    rustdoc().input("first.md").output(&single_dir).run();
+    let first = fs::read_to_string(single_dir.join("first.html")).unwrap();
+    assert!(first.contains(r#"<a href="second.md">"#));
+    assert!(first.contains(r#"<a href="second.md#details">"#));
+}
@@ -0,0 +1,42 @@
+// Checks that several Markdown files can be rendered at once, with heading IDs which are unique
+// across the files, and that `--markdown-index` writes a page linking to each of them in order.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("many");
+    rustdoc()
+        .input("first.md")
+        .arg("second.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-index")
+        .run();
+    let first = fs::read_to_string(out_dir.join("first.html")).unwrap();
+    assert!(first.contains("<title>First Page</title>"));
+    assert!(first.contains(r#"id="usage""#));
+    let second = fs::read_to_string(out_dir.join("second.html")).unwrap();
+    assert!(second.contains("<title>Second Page</title>"));
+    assert!(second.contains(r#"id="usage-1""#));
+    let index = fs::read_to_string(out_dir.join("index.html")).unwrap();
+    assert!(
+        index.contains(r#"<ol class="index"><li><a href="first.html">First Page</a></li><li>"#)
+    );
+    assert!(index.contains(r#"</li><li><a href="second.html">Second Page</a></li></ol>"#));
+
+    let default_dir = tmp_dir().join("default");
+    rustdoc()
+        .input("first.md")
+        .arg("second.md")
+        .output(&default_dir)
+        .arg("-Zunstable-options")
+        .run();
+    assert!(default_dir.join("first.html").exists());
+    assert!(default_dir.join("second.html").exists());
+    assert!(!default_dir.join("index.html").exists());
+
+    let output = rustdoc().input("first.md").arg("second.md").output(tmp_dir()).run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("the -Z unstable-options flag must be passed to render several"));
+}
@@ -0,0 +1,61 @@
+// Checks that `--markdown-math` loads MathJax in a rendered Markdown file and leaves its math
+// delimiters intact, that `--markdown-math-url` changes where MathJax is loaded from, that the
+// `--markdown-csp` policy allows both MathJax and its configuration, and that nothing is loaded
+// otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("math", &["--markdown-math"]);
+    assert!(html.contains(r#"<script src="mathjax-config.js"></script>"#));
+    assert!(!html.contains("window.MathJax"));
+    let config = fs::read_to_string(tmp_dir().join("math/mathjax-config.js")).unwrap();
+    assert!(config.contains("window.MathJax"));
+    assert!(html.contains(
+        r#"<script defer src="https://cdn.jsdelivr.net/npm/mathjax@3/es5/tex-mml-chtml.js">"#
+    ));
+    assert!(html.contains("$e^{i\\pi} + 1 = 0$"));
+    assert!(html.contains("$$x = 1$$"));
+
+    let html = render(
+        "math-url",
+        &["--markdown-math", "--markdown-math-url", "https://example.com/mathjax.js"],
+    );
+    assert!(html.contains(r#"<script defer src="https://example.com/mathjax.js">"#));
+    assert!(!html.contains("cdn.jsdelivr.net"));
+
+    let html = render("csp", &["--markdown-math", "--markdown-csp"]);
+    assert!(html.contains(r#"<script src="mathjax-config.js"></script>"#));
+    assert!(html.contains(
+        r#"content="script-src &#39;self&#39; https://cdn.jsdelivr.net; object-src &#39;none&#39;"#
+    ));
+
+    let html = render("default", &[]);
+    assert!(!html.contains("MathJax"));
+    assert!(!tmp_dir().join("default/mathjax-config.js").exists());
+
+    let output = rustdoc()
+        .input("input.md")
+        .output(tmp_dir().join("url-only"))
+        .arg("-Zunstable-options")
+        .args(&["--markdown-math-url", "https://example.com/mathjax.js"])
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("option `--markdown-math-url` requires `--markdown-math`"));
+
+    let output = rustdoc()
+        .input("input.md")
+        .output("-")
+        .arg("-Zunstable-options")
+        .arg("--markdown-math")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("`--markdown-math` cannot be used when writing to stdout"));
+}
@@ -0,0 +1,26 @@
+// Checks that the `author`, `date` and `description` metadata lines of a rendered Markdown file
+// emit `<meta>` tags, and that none are emitted without them in a file without prose.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("meta");
+    rustdoc().input("input.md").output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<meta name="author" content="Jane Doe">"#));
+    assert!(html.contains(r#"<meta name="date" content="2024">"#));
+    assert!(html.contains(
+        r#"<meta name="description" content="A page about &quot;things&quot; &amp; stuff">"#
+    ));
+    assert!(html.contains("<title>The Title</title>"));
+
+    let no_meta = tmp_dir().join("no-meta.md");
+    fs::write(&no_meta, "% The Title\n\n## A heading\n").unwrap();
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input(&no_meta).output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("no-meta.html")).unwrap();
+    assert!(!html.contains(r#"<meta name="author""#));
+    assert!(!html.contains(r#"<meta name="date""#));
+    assert!(!html.contains(r#"<meta name="description""#));
+}
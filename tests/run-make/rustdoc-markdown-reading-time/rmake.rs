@@ -0,0 +1,46 @@
+// Checks that `--markdown-reading-time` adds a reading time estimate to a rendered Markdown file,
+// that the estimate scales with the length of the text, and that it is absent by default.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input("input.md").output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("input.html")).unwrap();
+    assert!(!html.contains("min read"));
+
+    let short_dir = tmp_dir().join("short");
+    rustdoc()
+        .input("input.md")
+        .output(&short_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-reading-time")
+        .arg("4")
+        .run();
+    let html = fs::read_to_string(short_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<p class="reading-time">~2 min read</p>"#));
+
+    let long_md = tmp_dir().join("long.md");
+    let mut content = fs::read_to_string("input.md").unwrap();
+    content.push_str(&"Some more words to read.\n\n".repeat(40));
+    fs::write(&long_md, content).unwrap();
+    let long_dir = tmp_dir().join("long");
+    rustdoc()
+        .input(&long_md)
+        .output(&long_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-reading-time")
+        .arg("4")
+        .run();
+    let html = fs::read_to_string(long_dir.join("long.html")).unwrap();
+    assert!(html.contains(r#"<p class="reading-time">~52 min read</p>"#));
+
+    rustdoc()
+        .input("input.md")
+        .output(tmp_dir().join("zero"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-reading-time")
+        .arg("0")
+        .run_fail();
+}
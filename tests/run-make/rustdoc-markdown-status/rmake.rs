@@ -0,0 +1,21 @@
+// Checks that the `status` metadata of a rendered Markdown file is shown as a badge after the
+// title, with a class for the known statuses and `status-unknown` for the others.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("draft");
+    rustdoc().input("input.md").output(&out_dir).run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    let title = html.find(r#"<h1 class="title">The Design</h1>"#).unwrap();
+    let badge = html.find(r#"<span class="status status-draft">draft</span>"#).unwrap();
+    assert!(title < badge);
+
+    let other = tmp_dir().join("other.md");
+    fs::write(&other, "% The Design\n% status: <proposed>\n\nSome text.\n").unwrap();
+    let other_dir = tmp_dir().join("other");
+    rustdoc().input(&other).output(&other_dir).run();
+    let html = fs::read_to_string(other_dir.join("other.html")).unwrap();
+    assert!(html.contains(r#"<span class="status status-unknown">&lt;proposed&gt;</span>"#));
+}
@@ -0,0 +1,29 @@
+// Checks that a rendered Markdown file is written to stdout when the output is `-` and unstable
+// options are enabled, and that no file is created for it. Without unstable options, `-` is an
+// output directory like any other.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::path::Path;
+
+fn main() {
+    let output = rustdoc().input("input.md").output("-").arg("-Zunstable-options").run();
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    assert!(stdout.starts_with("<!DOCTYPE html>"));
+    assert!(stdout.contains("<title>The Title</title>"));
+    assert!(stdout.contains("Some text."));
+    assert!(!Path::new("-").exists());
+
+    let output = rustdoc()
+        .input("input.md")
+        .output("-")
+        .arg("-Zunstable-options")
+        .arg("--markdown-toc-file")
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("`--markdown-toc-file` cannot be used when writing to stdout"));
+
+    let input = std::env::current_dir().unwrap().join("input.md");
+    let output = rustdoc().input(&input).output("-").current_dir(tmp_dir()).run();
+    assert!(output.stdout.is_empty());
+    assert!(tmp_dir().join("-").join("input.html").exists());
+}
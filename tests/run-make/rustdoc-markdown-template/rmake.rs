@@ -0,0 +1,57 @@
+// Checks that `--markdown-template` renders a Markdown file into the given HTML file, that the
+// rendered content is not searched for placeholders, that the parts of the built-in page added by
+// other options have placeholders too, and that a template without the required placeholders is
+// rejected.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("template");
+    rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-template")
+        .arg("template.html")
+        .arg("--markdown-css")
+        .arg("style.css")
+        .run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains("<title>Tom &amp; Jerry - House Style</title>"), "{html}");
+    assert!(html.contains(r#"<link rel="stylesheet" href="style.css"></head>"#), "{html}");
+    assert!(html.contains("<code>{{css}}</code>"), "{html}");
+    assert!(html.contains("<footer>{{unknown}}</footer>"), "{html}");
+    assert!(!html.contains("rustdoc"), "{html}");
+
+    let out_dir = tmp_dir().join("full");
+    rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-template")
+        .arg("full.html")
+        .arg("--markdown-lang")
+        .arg("fr")
+        .arg("--markdown-theme-color")
+        .arg("#ff0000")
+        .arg("--markdown-back-to-top")
+        .run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<html lang="fr">"#), "{html}");
+    assert!(html.contains(r##"<meta name="theme-color" content="#ff0000"></head>"##), "{html}");
+    assert!(html.contains(r#"<h1 id="top">Tom &amp; Jerry</h1>"#), "{html}");
+    assert!(html.contains(r##"href="#top""##), "{html}");
+
+    let incomplete = tmp_dir().join("incomplete.html");
+    fs::write(&incomplete, "<html><title>{{title}}</title></html>").unwrap();
+    let output = rustdoc()
+        .input("input.md")
+        .output(tmp_dir().join("incomplete"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-template")
+        .arg(&incomplete)
+        .run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("is missing the `{{content}}` placeholder"), "{stderr}");
+}
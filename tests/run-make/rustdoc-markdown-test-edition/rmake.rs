@@ -0,0 +1,12 @@
+// Checks that the doctests of a Markdown file are compiled with the edition of their code block,
+// whatever the default edition is.
+
+use run_make_support::rustdoc;
+
+fn main() {
+    for edition in ["2015", "2018"] {
+        let output = rustdoc().input("input.md").arg("--test").arg("--edition").arg(edition).run();
+        let stdout = std::str::from_utf8(&output.stdout).unwrap();
+        assert!(stdout.contains("test result: ok. 2 passed"), "{stdout}");
+    }
+}
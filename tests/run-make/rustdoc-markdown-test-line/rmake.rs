@@ -0,0 +1,47 @@
+// Checks that `--markdown-test-line` runs only the doctest of a Markdown file at that line, with
+// or without the file name, and that `--test-args` are still passed to the test runner as they
+// are.
+
+use run_make_support::rustdoc;
+
+fn main() {
+    let output = rustdoc().input("input.md").arg("--test").arg("--test-args").arg("--list").run();
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    let mut lines: Vec<usize> = stdout
+        .lines()
+        .filter_map(|test| test.split_once("(line ")?.1.split_once(')')?.0.parse().ok())
+        .collect();
+    // The passing doctest comes first in the file.
+    lines.sort();
+    let [passing, failing] = lines[..] else { panic!("expected two doctests: {stdout}") };
+
+    for selector in [format!("{passing}"), format!(":{passing}"), format!("input.md:{passing}")] {
+        let output = rustdoc()
+            .input("input.md")
+            .arg("--test")
+            .arg("-Zunstable-options")
+            .arg("--markdown-test-line")
+            .arg(&selector)
+            .run();
+        let stdout = std::str::from_utf8(&output.stdout).unwrap();
+        assert!(stdout.contains("test result: ok. 1 passed"), "{stdout}");
+    }
+
+    rustdoc()
+        .input("input.md")
+        .arg("--test")
+        .arg("-Zunstable-options")
+        .arg("--markdown-test-line")
+        .arg(format!("{failing}"))
+        .run_fail();
+
+    // A `<file>:<line>` test argument is a filter of the test runner, which matches no test.
+    let output = rustdoc()
+        .input("input.md")
+        .arg("--test")
+        .arg("--test-args")
+        .arg(format!("input.md:{failing}"))
+        .run();
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    assert!(stdout.contains("test result: ok. 0 passed"), "{stdout}");
+}
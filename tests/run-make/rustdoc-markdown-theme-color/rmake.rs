@@ -0,0 +1,35 @@
+// Checks that `--markdown-theme-color` adds a `theme-color` meta to a rendered Markdown file, that
+// none is added otherwise, and that values which are not colors are rejected.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn render(name: &str, args: &[&str]) -> String {
+    let out_dir = tmp_dir().join(name);
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").args(args).run();
+    fs::read_to_string(out_dir.join("input.html")).unwrap()
+}
+
+fn main() {
+    let html = render("hex", &["--markdown-theme-color", "#1a2B3c"]);
+    assert!(html.contains(r##"<meta name="theme-color" content="#1a2B3c">"##));
+
+    let html = render("name", &["--markdown-theme-color", "navy"]);
+    assert!(html.contains(r#"<meta name="theme-color" content="navy">"#));
+
+    let html = render("none", &[]);
+    assert!(!html.contains("theme-color"));
+
+    // Neither a hexadecimal color nor a CSS color name.
+    for color in ["#12345", "banana"] {
+        let output = rustdoc()
+            .input("input.md")
+            .output(tmp_dir().join("invalid"))
+            .arg("-Zunstable-options")
+            .args(&["--markdown-theme-color", color])
+            .run_fail();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let expected = format!("must be a color like `#1a2b3c` or `navy`, got `{color}`");
+        assert!(stderr.contains(&expected), "{stderr}");
+    }
+}
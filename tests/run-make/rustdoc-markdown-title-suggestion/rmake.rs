@@ -0,0 +1,11 @@
+// Checks that rendering a Markdown file whose title line is missing the space after the `#`
+// suggests the title line that was probably meant.
+
+use run_make_support::{rustdoc, tmp_dir};
+
+fn main() {
+    let output = rustdoc().input("input.md").output(tmp_dir()).run_fail();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("invalid markdown file: no initial lines starting with `# ` or `%`"));
+    assert!(stderr.contains("did you mean `# Title` with a space?"));
+}
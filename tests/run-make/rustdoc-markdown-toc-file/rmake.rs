@@ -0,0 +1,33 @@
+// Checks that `--markdown-toc-file` writes the table of contents of a rendered Markdown file to
+// an `input.toc.html` file next to it, linking to the headings of the rendered page, and that no
+// such file is written otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("toc-file");
+    rustdoc()
+        .input("input.md")
+        .output(&out_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-toc-file")
+        .run();
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    let toc = fs::read_to_string(out_dir.join("input.toc.html")).unwrap();
+
+    assert!(toc.starts_with(r#"<nav id="TOC">"#));
+    let ids: Vec<&str> =
+        toc.split(r##"href="#"##).skip(1).map(|rest| &rest[..rest.find('"').unwrap()]).collect();
+    assert_eq!(ids, ["getting-started", "installing", "usage"]);
+    for id in ids {
+        assert!(html.contains(&format!(r#"id="{id}""#)));
+    }
+    // The rendered page keeps its own table of contents.
+    assert!(html.contains(&toc.trim_end()[r#"<nav id="TOC">"#.len()..]));
+
+    let out_dir = tmp_dir().join("no-toc-file");
+    rustdoc().input("input.md").output(&out_dir).arg("-Zunstable-options").run();
+    assert!(out_dir.join("input.html").exists());
+    assert!(!out_dir.join("input.toc.html").exists());
+}
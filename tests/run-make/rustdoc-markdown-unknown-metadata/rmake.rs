@@ -0,0 +1,20 @@
+// Checks that the metadata lines of a Markdown file with a misspelled key are reported with their
+// line, that the known ones, like `lang`, are used, and that other `%` lines with a colon, like a
+// time, are not reported.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let out_dir = tmp_dir().join("metadata");
+    let output = rustdoc().input("input.md").output(&out_dir).run();
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("input.md:3: unknown metadata key in `titel: A Typo`"), "{stderr}");
+    assert!(!stderr.contains("author"), "{stderr}");
+    assert!(!stderr.contains("lang"), "{stderr}");
+    assert!(!stderr.contains("10:00"), "{stderr}");
+
+    let html = fs::read_to_string(out_dir.join("input.html")).unwrap();
+    assert!(html.contains(r#"<html lang="fr">"#));
+    assert!(html.contains(r#"<meta name="author" content="Jane Doe">"#));
+}
@@ -0,0 +1,35 @@
+// Checks that `--markdown-viewport` sets the content of the viewport `<meta>` tag of a rendered
+// Markdown file, and that the default is kept otherwise.
+
+use run_make_support::{rustdoc, tmp_dir};
+use std::fs;
+
+fn main() {
+    let default_dir = tmp_dir().join("default");
+    rustdoc().input("input.md").output(&default_dir).run();
+    let html = fs::read_to_string(default_dir.join("input.html")).unwrap();
+    assert!(
+        html.contains(r#"<meta name="viewport" content="width=device-width, initial-scale=1.0">"#)
+    );
+
+    let custom_dir = tmp_dir().join("custom");
+    rustdoc()
+        .input("input.md")
+        .output(&custom_dir)
+        .arg("-Zunstable-options")
+        .arg("--markdown-viewport")
+        .arg("width=device-width, user-scalable=no")
+        .run();
+    let html = fs::read_to_string(custom_dir.join("input.html")).unwrap();
+    assert!(
+        html.contains(r#"<meta name="viewport" content="width=device-width, user-scalable=no">"#)
+    );
+
+    rustdoc()
+        .input("input.md")
+        .output(tmp_dir().join("empty"))
+        .arg("-Zunstable-options")
+        .arg("--markdown-viewport")
+        .arg("")
+        .run_fail();
+}
@@ -0,0 +1,32 @@
+//@ build-pass
+//@ compile-flags: -Zvalidate-mir -Zmir-opt-level=0 -Zmir-enable-passes=+ScalarReplacementOfAggregates
+
+// Check that SROA does not report the assignment of a constant aggregate as a use of a replaced
+// local. The local stays, and its fragments are read from it.
+
+const PAIR: (u32, u8) = (5, 9);
+
+struct Foo {
+    a: u8,
+    b: (),
+    c: &'static str,
+    d: Option<isize>,
+}
+
+const FOO: Foo = Foo { a: 5, b: (), c: "a", d: Some(-4) };
+
+fn constant() -> u32 {
+    let y = PAIR;
+    y.0 + y.1 as u32
+}
+
+fn constant_struct() -> u8 {
+    let y = FOO;
+    let Foo { a, b: _, c, d } = y;
+    a + c.len() as u8 + d.unwrap_or(0) as u8
+}
+
+fn main() {
+    constant();
+    constant_struct();
+}
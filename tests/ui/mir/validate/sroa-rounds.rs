@@ -0,0 +1,28 @@
+//@ build-pass
+//@ compile-flags: -Zvalidate-mir -Zmir-opt-level=2 -Cdebuginfo=2
+
+// Check that every round of flattening in SROA produces valid MIR. Arrays of tuples are flattened
+// over two rounds, and their debuginfo fragments project through `ConstantIndex`.
+
+#[derive(Copy, Clone)]
+struct Pair {
+    a: u8,
+    b: (u16, u32),
+}
+
+fn arrays(x: u32, y: u32) -> (u32, u32, u32, u32) {
+    let arr = [(x, y), (y, x)];
+    let [(a, b), (c, d)] = arr;
+    (a, b, c, d)
+}
+
+fn nested(p: Pair) -> u32 {
+    let q = p;
+    let (s, t) = q.b;
+    q.a as u32 + s as u32 + t
+}
+
+fn main() {
+    arrays(1, 2);
+    nested(Pair { a: 1, b: (2, 3) });
+}